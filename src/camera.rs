@@ -0,0 +1,49 @@
+// camera.rs
+
+use raylib::prelude::*;
+use crate::player::Player;
+
+// Modelo de proyección del renderizador 3D: un plano de proyección del ancho
+// del framebuffer colocado a la distancia que corresponde al FOV del jugador
+pub struct Camera {
+    pub pos: Vector2,
+    pub a: f32,
+    pub width: u32,
+    pub height: u32,
+    pub projection_distance: f32,
+}
+
+impl Camera {
+    pub fn from_player(player: &Player, width: u32, height: u32) -> Self {
+        let projection_distance = (width as f32 / 2.0) / (player.fov / 2.0).tan();
+
+        Camera {
+            pos: player.pos,
+            a: player.a,
+            width,
+            height,
+            projection_distance,
+        }
+    }
+
+    // Altura de la línea del horizonte en pantalla
+    pub fn horizon(&self) -> f32 {
+        self.height as f32 / 2.0
+    }
+
+    // Ángulo del rayo que pasa por el centro de una columna de la pantalla
+    pub fn ray_angle(&self, column: u32) -> f32 {
+        let offset = column as f32 + 0.5 - self.width as f32 / 2.0;
+        self.a + (offset / self.projection_distance).atan()
+    }
+
+    // Distancia medida sobre la dirección de la cámara (corrige el efecto ojo de pez)
+    pub fn perpendicular_distance(&self, distance: f32, ray_angle: f32) -> f32 {
+        distance * (ray_angle - self.a).cos()
+    }
+
+    // Altura en pantalla de algo de `world_height` unidades a una distancia perpendicular
+    pub fn projected_height(&self, world_height: f32, perpendicular_distance: f32) -> f32 {
+        world_height * self.projection_distance / perpendicular_distance.max(0.0001)
+    }
+}
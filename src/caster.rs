@@ -1,51 +1,431 @@
 // caster.rs
 
 use raylib::color::Color;
+use raylib::prelude::Vector2;
 
+use std::f32::consts::PI;
+
+use crate::door::{DOOR, Door, DoorAxis, Doors, SWITCH};
 use crate::framebuffer::Framebuffer;
-use crate::maze::Maze;
-use crate::player::Player;
+use crate::line::line;
+use crate::maze::{Maze, cell_at, maze_width, world_to_cell};
+
+// Rayos extra repartidos en la vuelta completa para redondear el borde del
+// polígono de visibilidad donde no hay paredes cerca
+const FILL_RAYS: usize = 64;
+
+// Cara de la celda golpeada por el rayo
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Face {
+  North, // y mínima de la celda
+  South, // y máxima de la celda
+  East,  // x máxima de la celda
+  West,  // x mínima de la celda
+}
+
+// Qué ve el rayo al salir de la cuadrícula (o al pasar por el hueco de una fila corta)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Void {
+  Open,        // cielo abierto: el rayo sigue y no golpea nada
+  Solid(char), // se comporta como una pared con esa textura
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RayConfig {
+  pub max_distance: f32,
+  pub void: Void,
+}
 
 pub struct Intersect {
+  pub hit: bool,      // false si el rayo no golpeó nada antes de max_distance
   pub distance: f32,
-  pub impact: char
+  pub impact: char,
+  pub point: Vector2, // punto de impacto en coordenadas del mundo
+  pub i: usize,       // columna de la celda golpeada (recortada al borde si es vacío)
+  pub j: usize,       // fila de la celda golpeada (recortada al borde si es vacío)
+  pub face: Face,
+  pub tex_u: f32,     // coordenada de textura a lo largo de la pared (0.0 a 1.0)
+}
+
+// Píxeles del mapa 2D por unidad del mundo: el maze entero entra en el
+// framebuffer, sea cual sea la resolución
+pub fn map_scale(framebuffer: &Framebuffer, maze: &Maze, block_size: usize) -> f32 {
+  let world_width = (maze_width(maze) * block_size) as f32;
+  let world_height = (maze.len() * block_size) as f32;
+  if world_width == 0.0 || world_height == 0.0 {
+    return 1.0;
+  }
+  (framebuffer.width as f32 / world_width).min(framebuffer.height as f32 / world_height)
 }
 
+// Traza el rayo y lo dibuja en el mapa 2D desde `origin` hasta el impacto
 pub fn cast_ray(
   framebuffer: &mut Framebuffer,
   maze: &Maze,
-  player: &Player,
+  doors: &Doors,
+  origin: Vector2,
   a: f32,
   block_size: usize,
-  draw_line: bool,
+  config: &RayConfig,
 ) -> Intersect {
-  let mut d = 0.0;
+  let intersect = march(maze, doors, origin, a, block_size, config);
 
+  let scale = map_scale(framebuffer, maze, block_size);
   framebuffer.set_current_color(Color::WHITESMOKE);
+  line(framebuffer, origin * scale, intersect.point * scale);
+
+  intersect
+}
+
+// Primer impacto de un rayo desde `origin` en la dirección `a`, sin dibujar
+// nada. Fuera de la cuadrícula el rayo sigue de largo.
+pub fn raycast(maze: &Maze, doors: &Doors, origin: Vector2, a: f32, max_distance: f32, block_size: usize) -> Intersect {
+  march(maze, doors, origin, a, block_size, &RayConfig { max_distance, void: Void::Open })
+}
+
+// Si no hay paredes ni puertas cerradas entre dos puntos del mundo
+pub fn has_line_of_sight(maze: &Maze, doors: &Doors, from: Vector2, to: Vector2, block_size: usize) -> bool {
+  let offset = to - from;
+  let distance = offset.length();
+  if distance == 0.0 {
+    return true;
+  }
+  !raycast(maze, doors, from, offset.y.atan2(offset.x), distance, block_size).hit
+}
+
+// Polígono de lo que se ve desde `origin` hasta `max_distance`, como puntos
+// ordenados por ángulo. Además de los rayos de relleno se apunta justo a cada
+// esquina de pared cercana (y apenas a sus costados) para que los bordes de
+// las sombras queden exactos.
+pub fn visibility_polygon(
+  maze: &Maze,
+  doors: &Doors,
+  origin: Vector2,
+  max_distance: f32,
+  block_size: usize,
+) -> Vec<Vector2> {
+  let size = block_size as f32;
+  let mut angles: Vec<f32> = (0..FILL_RAYS).map(|k| k as f32 / FILL_RAYS as f32 * 2.0 * PI).collect();
+
+  let reach = (max_distance / size).ceil() as isize + 1;
+  let (ci, cj) = world_to_cell(origin, size);
+  for j in cj - reach..=cj + reach {
+    for i in ci - reach..=ci + reach {
+      // Solo las celdas que march trata como pared (la salida también lo es)
+      match cell_at(maze, i, j) {
+        None | Some(' ') | Some(SWITCH) | Some(DOOR) => continue,
+        Some(_) => {}
+      }
+      for (di, dj) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+        let corner = Vector2::new((i + di) as f32 * size, (j + dj) as f32 * size);
+        if corner.distance_to(origin) > max_distance {
+          continue;
+        }
+        let a = (corner.y - origin.y).atan2(corner.x - origin.x);
+        angles.extend([a - 0.0001, a, a + 0.0001]);
+      }
+    }
+  }
+
+  for a in angles.iter_mut() {
+    *a = a.rem_euclid(2.0 * PI);
+  }
+  angles.sort_by(f32::total_cmp);
+  angles.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+
+  angles
+    .into_iter()
+    .map(|a| raycast(maze, doors, origin, a, max_distance, block_size).point)
+    .collect()
+}
+
+// Recorrido DDA de la cuadrícula a lo largo de un rayo. Cada paso es la
+// próxima celda que cruza, con la distancia a la que entra y la cara por la
+// que entra. La celda de partida no se recorre: queda en `i` y `j` antes del
+// primer paso. No termina nunca; el que lo usa decide cuándo cortar.
+pub struct GridCells {
+  pub i: isize,
+  pub j: isize,
+  step_i: isize,
+  step_j: isize,
+  side_x: f32,  // distancia hasta la próxima línea vertical de la cuadrícula
+  side_y: f32,  // distancia hasta la próxima línea horizontal
+  delta_x: f32, // distancia para atravesar una celda completa en x
+  delta_y: f32,
+}
+
+impl GridCells {
+  // Distancia a la que el rayo sale de la celda actual
+  pub fn exit(&self) -> f32 {
+    self.side_x.min(self.side_y)
+  }
+
+  // Avanza a la próxima celda: (columna, fila, distancia de entrada, cara)
+  pub fn step(&mut self) -> (isize, isize, f32, Face) {
+    if self.side_x < self.side_y {
+      let distance = self.side_x;
+      self.side_x += self.delta_x;
+      self.i += self.step_i;
+      let face = if self.step_i > 0 { Face::West } else { Face::East };
+      (self.i, self.j, distance, face)
+    } else {
+      let distance = self.side_y;
+      self.side_y += self.delta_y;
+      self.j += self.step_j;
+      let face = if self.step_j > 0 { Face::North } else { Face::South };
+      (self.i, self.j, distance, face)
+    }
+  }
+}
+
+impl Iterator for GridCells {
+  type Item = (isize, isize, f32, Face);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    Some(self.step())
+  }
+}
+
+// Celdas que cruza un rayo que sale de `origin` con ángulo `a`
+pub fn grid_cells(origin: Vector2, a: f32, block_size: f32) -> GridCells {
+  let (dir_x, dir_y) = (a.cos(), a.sin());
+  let (i, j) = world_to_cell(origin, block_size);
+
+  let delta_x = if dir_x == 0.0 { f32::INFINITY } else { (block_size / dir_x).abs() };
+  let delta_y = if dir_y == 0.0 { f32::INFINITY } else { (block_size / dir_y).abs() };
+
+  // Dirección del paso y distancia hasta la primera línea de la cuadrícula
+  let (step_i, side_x) = if dir_x < 0.0 {
+    (-1, (origin.x - i as f32 * block_size) / -dir_x)
+  } else if dir_x > 0.0 {
+    (1, ((i + 1) as f32 * block_size - origin.x) / dir_x)
+  } else {
+    (0, f32::INFINITY)
+  };
+  let (step_j, side_y) = if dir_y < 0.0 {
+    (-1, (origin.y - j as f32 * block_size) / -dir_y)
+  } else if dir_y > 0.0 {
+    (1, ((j + 1) as f32 * block_size - origin.y) / dir_y)
+  } else {
+    (0, f32::INFINITY)
+  };
+
+  GridCells { i, j, step_i, step_j, side_x, side_y, delta_x, delta_y }
+}
+
+// Recorre la cuadrícula celda por celda (DDA) hasta encontrar una pared.
+// No dibuja nada, así que se puede llamar desde varios hilos a la vez.
+pub fn march(maze: &Maze, doors: &Doors, origin: Vector2, a: f32, block_size: usize, config: &RayConfig) -> Intersect {
+  let size = block_size as f32;
+  let rows = maze.len() as isize;
+  let cols = maze_width(maze) as isize;
+  let dir_x = a.cos();
+  let dir_y = a.sin();
+  let mut cells = grid_cells(origin, a, size);
+
+  // El rayo puede salir desde adentro de una puerta (el jugador empujando
+  // la hoja): la celda de partida también se revisa
+  let (i, j) = (cells.i, cells.j);
+  if cell_at(maze, i, j) == Some(DOOR) {
+    if let Some(door) = doors.get(i, j) {
+      if let Some(hit) = door_hit(door, origin, (dir_x, dir_y), (i, j), size, (0.0, cells.exit())) {
+        if hit.distance <= config.max_distance {
+          return hit;
+        }
+      }
+    }
+  }
 
   loop {
-    let cos = d * a.cos();
-    let sin = d * a.sin();
-    let x = (player.pos.x + cos) as usize;
-    let y = (player.pos.y + sin) as usize;
-
-    let i = x / block_size;
-    let j = y / block_size;
-
-    if maze[j][i] != ' ' {
-      return Intersect{
-        distance: d,
-        impact: maze[j][i]
+    let (i, j, distance, face) = cells.step();
+    if distance > config.max_distance {
+      return miss(origin, dir_x, dir_y, config.max_distance, i, j, face);
+    }
+
+    // Fuera de la cuadrícula y alejándose de ella: ya no puede golpear nada
+    let outside = i < 0 || j < 0 || i >= cols || j >= rows;
+    let leaving = (i < 0 && dir_x <= 0.0)
+      || (j < 0 && dir_y <= 0.0)
+      || (i >= cols && dir_x >= 0.0)
+      || (j >= rows && dir_y >= 0.0);
+
+    let cell = match cell_at(maze, i, j) {
+      Some(cell) => cell,
+      None => match config.void {
+        Void::Solid(cell) => cell,
+        Void::Open if outside && leaving => {
+          return miss(origin, dir_x, dir_y, config.max_distance, i, j, face);
+        }
+        Void::Open => ' ',
+      },
+    };
+
+    // Puerta: la hoja es una pared delgada en el medio de la celda, corrida
+    // hacia el costado según cuánto esté abierta
+    if cell == DOOR {
+      if let Some(door) = doors.get(i, j) {
+        if let Some(hit) = door_hit(door, origin, (dir_x, dir_y), (i, j), size, (distance, cells.exit())) {
+          return hit;
+        }
+        continue;
+      }
+    }
+
+    if cell != ' ' && cell != SWITCH {
+      let point = Vector2::new(origin.x + distance * dir_x, origin.y + distance * dir_y);
+
+      // Posición del impacto dentro de la celda sobre el eje paralelo a la pared
+      let tex_u = match face {
+        Face::North | Face::South => point.x / size - i as f32,
+        Face::East | Face::West => point.y / size - j as f32,
+      }.clamp(0.0, 1.0);
+
+      return Intersect {
+        hit: true,
+        distance,
+        impact: cell,
+        point,
+        i: i.max(0) as usize,
+        j: j.max(0) as usize,
+        face,
+        tex_u,
       };
     }
+  }
+}
 
-    if draw_line {
-      framebuffer.set_pixel(x as u32, y as u32);
+// Impacto con la hoja de una puerta si el rayo la cruza dentro de la celda
+// `span` es la distancia a la que el rayo entra y sale de la celda
+fn door_hit(
+  door: &Door,
+  origin: Vector2,
+  (dir_x, dir_y): (f32, f32),
+  (i, j): (isize, isize),
+  size: f32,
+  (enter, exit): (f32, f32),
+) -> Option<Intersect> {
+  // Distancia hasta el plano de la hoja y posición a lo largo de la hoja (0.0 a 1.0)
+  let (distance, along, face) = match door.axis {
+    DoorAxis::Horizontal if dir_y != 0.0 => {
+      let distance = ((j as f32 + 0.5) * size - origin.y) / dir_y;
+      let along = (origin.x + distance * dir_x) / size - i as f32;
+      (distance, along, if dir_y > 0.0 { Face::North } else { Face::South })
+    }
+    DoorAxis::Vertical if dir_x != 0.0 => {
+      let distance = ((i as f32 + 0.5) * size - origin.x) / dir_x;
+      let along = (origin.y + distance * dir_y) / size - j as f32;
+      (distance, along, if dir_x > 0.0 { Face::West } else { Face::East })
     }
+    _ => return None, // rayo paralelo a la hoja
+  };
 
-    d += 10.0;
+  // La parte abierta de la puerta deja pasar el rayo
+  if distance < enter || distance > exit || along < door.open || along > 1.0 {
+    return None;
   }
+
+  Some(Intersect {
+    hit: true,
+    distance,
+    impact: DOOR,
+    point: Vector2::new(origin.x + distance * dir_x, origin.y + distance * dir_y),
+    i: i as usize,
+    j: j as usize,
+    face,
+    tex_u: (along - door.open).clamp(0.0, 1.0),
+  })
 }
 
+// Resultado de un rayo que se pierde en el vacío
+fn miss(origin: Vector2, dir_x: f32, dir_y: f32, max_distance: f32, i: isize, j: isize, face: Face) -> Intersect {
+  Intersect {
+    hit: false,
+    distance: max_distance,
+    impact: ' ',
+    point: Vector2::new(origin.x + max_distance * dir_x, origin.y + max_distance * dir_y),
+    i: i.max(0) as usize,
+    j: j.max(0) as usize,
+    face,
+    tex_u: 0.0,
+  }
+}
 
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::maze::Level;
 
+  const BLOCK: usize = 100;
+
+  fn grid(rows: &[&str]) -> Maze {
+    rows.iter().map(|row| row.chars().collect()).collect()
+  }
+
+  fn cast(maze: &Maze, origin: Vector2, a: f32, void: Void) -> Intersect {
+    let doors = Doors::new(&Level::from_maze("prueba", maze.clone()));
+    march(maze, &doors, origin, a, BLOCK, &RayConfig { max_distance: 5000.0, void })
+  }
+
+  fn room() -> Maze {
+    grid(&["AAAAA", "A   A", "A   A", "A   A", "AAAAA"])
+  }
+
+  fn assert_close(found: f32, expected: f32) {
+    assert!((found - expected).abs() < 1e-3, "se esperaba {}, se obtuvo {}", expected, found);
+  }
+
+  #[test]
+  fn axis_aligned_rays_hit_the_nearest_wall() {
+    let origin = Vector2::new(250.0, 250.0);
+    let cases = [
+      (0.0, Face::West, (4, 2)),
+      (PI / 2.0, Face::North, (2, 4)),
+      (PI, Face::East, (0, 2)),
+      (3.0 * PI / 2.0, Face::South, (2, 0)),
+    ];
+    for (a, face, (i, j)) in cases {
+      let hit = cast(&room(), origin, a, Void::Open);
+      assert!(hit.hit);
+      assert_close(hit.distance, 150.0);
+      assert_eq!(hit.face, face);
+      assert_eq!(hit.impact, 'A');
+      assert_eq!((hit.i, hit.j), (i, j));
+    }
+  }
+
+  #[test]
+  fn diagonal_ray_hits_at_the_exact_distance() {
+    let hit = cast(&room(), Vector2::new(250.0, 200.0), PI / 4.0, Void::Open);
+    assert!(hit.hit);
+    assert_close(hit.distance, 150.0 * 2.0_f32.sqrt());
+    assert_eq!(hit.face, Face::West);
+    assert_eq!((hit.i, hit.j), (4, 3));
+    assert_close(hit.point.x, 400.0);
+    assert_close(hit.point.y, 350.0);
+    assert_close(hit.tex_u, 0.5);
+  }
+
+  #[test]
+  fn ray_through_a_grid_corner_stops_at_the_corner() {
+    let hit = cast(&room(), Vector2::new(250.0, 250.0), PI / 4.0, Void::Open);
+    assert!(hit.hit);
+    assert_close(hit.distance, 150.0 * 2.0_f32.sqrt());
+    assert_close(hit.point.x, 400.0);
+    assert_close(hit.point.y, 400.0);
+  }
+
+  #[test]
+  fn open_void_lets_the_ray_leave_the_grid() {
+    let maze = grid(&["AAA", "A  ", "AAA"]);
+    let origin = Vector2::new(150.0, 150.0);
+
+    let miss = cast(&maze, origin, 0.0, Void::Open);
+    assert!(!miss.hit);
+    assert_close(miss.distance, 5000.0);
+
+    let wall = cast(&maze, origin, 0.0, Void::Solid('B'));
+    assert!(wall.hit);
+    assert_eq!(wall.impact, 'B');
+    assert_close(wall.distance, 150.0);
+  }
+}
@@ -0,0 +1,139 @@
+// collision.rs
+
+use raylib::prelude::*;
+use crate::door::{DOOR, Doors};
+use crate::maze::{Maze, cell_at, is_walkable, world_to_cell};
+
+// Iteraciones de resolución por paso: con esquinas puede haber varios contactos a la vez
+const RESOLVE_ITERATIONS: usize = 4;
+
+// Rectángulo sólido alineado a los ejes, en coordenadas del mundo
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: Vector2,
+    pub max: Vector2,
+}
+
+impl Aabb {
+    pub fn new(min: Vector2, max: Vector2) -> Self {
+        Aabb { min, max }
+    }
+
+    // Punto del rectángulo más cercano a `point`
+    pub fn closest_point(&self, point: Vector2) -> Vector2 {
+        Vector2::new(
+            point.x.clamp(self.min.x, self.max.x),
+            point.y.clamp(self.min.y, self.max.y),
+        )
+    }
+}
+
+// Contacto entre un círculo y un sólido
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    pub normal: Vector2, // apunta desde el sólido hacia el centro del círculo
+    pub depth: f32,      // cuánto hay que empujar el círculo para separarlo
+}
+
+// Sólidos de una celda. Una pared ocupa la celda completa, igual que una
+// salida con candado sin su llave; una puerta solo la hoja que queda sin abrir
+pub fn cell_colliders(maze: &Maze, doors: &Doors, i: isize, j: isize, block_size: f32) -> Vec<Aabb> {
+    let cell = cell_at(maze, i, j);
+    if cell == Some(DOOR) {
+        return doors.get(i, j).and_then(|door| door.collider(i, j, block_size)).into_iter().collect();
+    }
+    if is_walkable(cell) && doors.locked_goal(i, j).is_none() {
+        return Vec::new();
+    }
+
+    // Paredes y todo lo que queda fuera del maze bloquean la celda entera
+    let min = Vector2::new(i as f32 * block_size, j as f32 * block_size);
+    vec![Aabb::new(min, min + Vector2::new(block_size, block_size))]
+}
+
+// Contacto de un círculo con una caja, si se superponen
+pub fn circle_vs_aabb(center: Vector2, radius: f32, aabb: &Aabb) -> Option<Contact> {
+    let closest = aabb.closest_point(center);
+    let offset = center - closest;
+    let distance = offset.length();
+
+    if distance >= radius {
+        return None;
+    }
+
+    if distance > 0.0 {
+        return Some(Contact { normal: offset / distance, depth: radius - distance });
+    }
+
+    // El centro quedó dentro de la caja: salir por el lado más cercano
+    let exits = [
+        (center.x - aabb.min.x, Vector2::new(-1.0, 0.0)),
+        (aabb.max.x - center.x, Vector2::new(1.0, 0.0)),
+        (center.y - aabb.min.y, Vector2::new(0.0, -1.0)),
+        (aabb.max.y - center.y, Vector2::new(0.0, 1.0)),
+    ];
+    let (gap, normal) = exits
+        .into_iter()
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap_or((0.0, Vector2::new(0.0, -1.0)));
+    Some(Contact { normal, depth: gap + radius })
+}
+
+// Sólidos de las celdas que puede tocar un círculo
+fn nearby_colliders(maze: &Maze, doors: &Doors, center: Vector2, radius: f32, block_size: f32) -> Vec<Aabb> {
+    let (min_i, min_j) = world_to_cell(center - Vector2::new(radius, radius), block_size);
+    let (max_i, max_j) = world_to_cell(center + Vector2::new(radius, radius), block_size);
+
+    let mut colliders = Vec::new();
+    for j in min_j..=max_j {
+        for i in min_i..=max_i {
+            colliders.extend(cell_colliders(maze, doors, i, j, block_size));
+        }
+    }
+    colliders
+}
+
+// Contacto más profundo del círculo con el maze
+fn deepest_contact(maze: &Maze, doors: &Doors, center: Vector2, radius: f32, block_size: f32) -> Option<Contact> {
+    nearby_colliders(maze, doors, center, radius, block_size)
+        .iter()
+        .filter_map(|aabb| circle_vs_aabb(center, radius, aabb))
+        .max_by(|a, b| a.depth.total_cmp(&b.depth))
+}
+
+// Mueve un círculo `motion` unidades deslizándolo por las paredes: en cada
+// contacto se quita la componente del movimiento que entra en la pared y se
+// conserva la tangencial. Devuelve la nueva posición del centro.
+pub fn move_and_slide(
+    maze: &Maze,
+    doors: &Doors,
+    start: Vector2,
+    motion: Vector2,
+    radius: f32,
+    block_size: f32,
+) -> Vector2 {
+    // Pasos de a lo sumo medio radio para no atravesar paredes finas
+    let max_step = (radius * 0.5).max(0.001);
+    let substeps = (motion.length() / max_step).ceil().max(1.0) as usize;
+    let mut remaining = motion / substeps as f32;
+    let mut pos = start;
+
+    for _ in 0..substeps {
+        pos += remaining;
+
+        for _ in 0..RESOLVE_ITERATIONS {
+            let Some(contact) = deepest_contact(maze, doors, pos, radius, block_size) else {
+                break;
+            };
+            pos += contact.normal * contact.depth;
+
+            // Proyectar el movimiento que falta sobre la tangente de la pared
+            let into_wall = remaining.dot(contact.normal);
+            if into_wall < 0.0 {
+                remaining -= contact.normal * into_wall;
+            }
+        }
+    }
+
+    pos
+}
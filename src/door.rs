@@ -0,0 +1,204 @@
+// door.rs
+
+use raylib::prelude::*;
+use std::collections::HashMap;
+
+use crate::collision::{Aabb, circle_vs_aabb};
+use crate::key::{Inventory, KeyColor};
+use crate::maze::{Level, Maze, cell_at, is_walkable, world_to_cell};
+
+pub const DOOR: char = 'D';
+pub const SWITCH: char = 's';
+
+const DOOR_SPEED: f32 = 1.5;   // fracción de la puerta que se abre por segundo
+const DOOR_THICKNESS: f32 = 0.1; // grosor de la hoja, en celdas
+const USE_REACH: f32 = 0.8;    // distancia en celdas a la que se alcanza una puerta
+const TOUCH_MARGIN: f32 = 0.05; // holgura en celdas para notar que se empuja una salida
+
+// Orientación de la hoja de la puerta dentro de su celda
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DoorAxis {
+    Horizontal, // hoja a lo largo de x: cierra un pasillo norte-sur
+    Vertical,   // hoja a lo largo de y: cierra un pasillo este-oeste
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Door {
+    pub axis: DoorAxis,
+    pub open: f32,     // 0.0 cerrada, 1.0 abierta del todo
+    pub opening: bool, // hacia dónde se está moviendo
+    pub lock: Option<KeyColor>, // llave que hace falta para abrirla
+}
+
+impl Door {
+    // Hoja de la puerta como caja sólida; None si está abierta del todo.
+    // La hoja va por el centro de la celda y se desliza hacia la pared del costado.
+    pub fn collider(&self, i: isize, j: isize, block_size: f32) -> Option<Aabb> {
+        if self.open >= 1.0 {
+            return None;
+        }
+
+        let (x, y) = (i as f32 * block_size, j as f32 * block_size);
+        let half = DOOR_THICKNESS * block_size / 2.0;
+        let slide = self.open * block_size;
+        Some(match self.axis {
+            DoorAxis::Horizontal => Aabb::new(
+                Vector2::new(x + slide, y + block_size / 2.0 - half),
+                Vector2::new(x + block_size, y + block_size / 2.0 + half),
+            ),
+            DoorAxis::Vertical => Aabb::new(
+                Vector2::new(x + block_size / 2.0 - half, y + slide),
+                Vector2::new(x + block_size / 2.0 + half, y + block_size),
+            ),
+        })
+    }
+}
+
+// Resultado de intentar usar una puerta
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DoorUse {
+    Nothing,           // no había ninguna puerta al alcance
+    Toggled,
+    Locked(KeyColor),  // falta la llave de ese color
+}
+
+// Estado de todas las puertas del nivel, a qué puertas abre cada interruptor
+// y qué salidas siguen con candado
+pub struct Doors {
+    doors: HashMap<(usize, usize), Door>,
+    links: Vec<((usize, usize), (usize, usize))>,
+    locked_goals: HashMap<(usize, usize), KeyColor>,
+}
+
+impl Doors {
+    // Todas las puertas del nivel, cerradas
+    pub fn new(level: &Level) -> Self {
+        let mut doors = HashMap::new();
+        for (j, row) in level.maze.iter().enumerate() {
+            for (i, &cell) in row.iter().enumerate() {
+                if cell != DOOR {
+                    continue;
+                }
+                let axis = door_axis(&level.maze, i, j).unwrap_or(DoorAxis::Horizontal);
+                let lock = level.locks.iter().find(|(_, cell)| *cell == (i, j)).map(|&(key, _)| key);
+                doors.insert((i, j), Door { axis, open: 0.0, opening: false, lock });
+            }
+        }
+
+        let locked_goals = level
+            .locks
+            .iter()
+            .filter(|(_, (i, j))| level.maze[*j][*i] == 'g')
+            .map(|&(key, cell)| (cell, key))
+            .collect();
+
+        Doors { doors, links: level.switches.clone(), locked_goals }
+    }
+
+    pub fn get(&self, i: isize, j: isize) -> Option<&Door> {
+        if i < 0 || j < 0 {
+            return None;
+        }
+        self.doors.get(&(i as usize, j as usize))
+    }
+
+    // Llave que le falta a la salida de la celda; la salida es sólida mientras tanto
+    pub fn locked_goal(&self, i: isize, j: isize) -> Option<KeyColor> {
+        if i < 0 || j < 0 {
+            return None;
+        }
+        self.locked_goals.get(&(i as usize, j as usize)).copied()
+    }
+
+    // Llave de la salida con candado contra la que está apoyado un círculo
+    pub fn locked_goal_touching(&self, center: Vector2, radius: f32, block_size: f32) -> Option<KeyColor> {
+        let reach = radius + TOUCH_MARGIN * block_size;
+        self.locked_goals
+            .iter()
+            .find(|&(&(i, j), _)| {
+                let min = Vector2::new(i as f32 * block_size, j as f32 * block_size);
+                let cell = Aabb::new(min, min + Vector2::new(block_size, block_size));
+                circle_vs_aabb(center, reach, &cell).is_some()
+            })
+            .map(|(_, &key)| key)
+    }
+
+    // Juntar una llave abre las salidas de su color
+    pub fn unlock(&mut self, key: KeyColor) {
+        self.locked_goals.retain(|_, lock| *lock != key);
+    }
+
+    // Avanza la animación de las puertas `dt` segundos
+    pub fn update(&mut self, dt: f32) {
+        for door in self.doors.values_mut() {
+            let target = if door.opening { 1.0 } else { 0.0 };
+            let step = DOOR_SPEED * dt;
+            door.open = if door.open < target {
+                (door.open + step).min(target)
+            } else {
+                (door.open - step).max(target)
+            };
+        }
+    }
+
+    // Abre o cierra la puerta que el jugador tiene enfrente. No cierra la
+    // puerta en la que está parado. Las puertas con candado solo se mueven
+    // si el inventario tiene su llave.
+    pub fn use_door(&mut self, pos: Vector2, a: f32, block_size: f32, inventory: &Inventory) -> DoorUse {
+        let here = world_to_cell(pos, block_size);
+        let ahead = world_to_cell(pos + Vector2::new(a.cos(), a.sin()) * (USE_REACH * block_size), block_size);
+
+        let unlocked = |door: &Door| match door.lock {
+            Some(key) if !inventory.has(key) => Err(DoorUse::Locked(key)),
+            _ => Ok(()),
+        };
+
+        if ahead != here && ahead.0 >= 0 && ahead.1 >= 0 {
+            if let Some(door) = self.doors.get_mut(&(ahead.0 as usize, ahead.1 as usize)) {
+                if let Err(locked) = unlocked(door) {
+                    return locked;
+                }
+                door.opening = !door.opening;
+                return DoorUse::Toggled;
+            }
+        }
+        if here.0 >= 0 && here.1 >= 0 {
+            if let Some(door) = self.doors.get_mut(&(here.0 as usize, here.1 as usize)) {
+                door.opening = true;
+                return DoorUse::Toggled;
+            }
+        }
+        DoorUse::Nothing
+    }
+
+    // Pisar un interruptor abre sus puertas; sin enlaces abre todas.
+    // Los interruptores nunca abren una puerta con candado.
+    pub fn press_switch(&mut self, cell: (usize, usize)) {
+        let linked: Vec<(usize, usize)> = self
+            .links
+            .iter()
+            .filter(|(switch, _)| *switch == cell)
+            .map(|&(_, door)| door)
+            .collect();
+
+        for (position, door) in self.doors.iter_mut() {
+            if door.lock.is_none() && (linked.is_empty() || linked.contains(position)) {
+                door.opening = true;
+            }
+        }
+    }
+}
+
+// Orientación que le corresponde a una puerta según las paredes que la enmarcan
+pub fn door_axis(maze: &Maze, i: usize, j: usize) -> Option<DoorAxis> {
+    let (i, j) = (i as isize, j as isize);
+    let solid = |ni: isize, nj: isize| !is_walkable(cell_at(maze, ni, nj));
+
+    if solid(i - 1, j) && solid(i + 1, j) {
+        Some(DoorAxis::Horizontal)
+    } else if solid(i, j - 1) && solid(i, j + 1) {
+        Some(DoorAxis::Vertical)
+    } else {
+        None
+    }
+}
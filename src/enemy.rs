@@ -0,0 +1,189 @@
+// enemy.rs
+
+use raylib::prelude::*;
+
+use crate::caster::has_line_of_sight;
+use crate::collision::move_and_slide;
+use crate::door::{DOOR, Doors};
+use crate::maze::{Level, Maze, cell_at, is_walkable, world_to_cell};
+use crate::solver::astar;
+use crate::sprite::Sprite;
+
+const ENEMY_RADIUS: f32 = 0.3;      // radio de colisión, en celdas
+const SIGHT_RANGE: f32 = 6.0;       // distancia máxima a la que ven al jugador, en celdas
+const REPATH_INTERVAL: f32 = 0.3;   // segundos entre recálculos del camino
+
+// Habitantes de la mente que recorren el laberinto
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EnemyKind {
+    Forgetter,  // Olvidadores: lentos
+    MindWorker, // trabajadores de la mente: más rápidos
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 2] = [EnemyKind::Forgetter, EnemyKind::MindWorker];
+
+    // Nombre que se usa en los archivos de nivel
+    pub fn config_name(self) -> &'static str {
+        match self {
+            EnemyKind::Forgetter => "olvidador",
+            EnemyKind::MindWorker => "trabajador",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<EnemyKind> {
+        let name = name.trim().to_lowercase();
+        EnemyKind::ALL.into_iter().find(|kind| kind.config_name() == name)
+    }
+
+    // Textura del TextureManager
+    pub fn texture(self) -> char {
+        match self {
+            EnemyKind::Forgetter => 'u',
+            EnemyKind::MindWorker => 'w',
+        }
+    }
+
+    // Color del resplandor y del punto en el minimapa
+    pub fn color(self) -> Color {
+        match self {
+            EnemyKind::Forgetter => Color::new(70, 130, 180, 255),
+            EnemyKind::MindWorker => Color::new(0, 170, 120, 255),
+        }
+    }
+
+    // Velocidades en celdas por segundo: (patrulla, persecución)
+    fn speeds(self) -> (f32, f32) {
+        match self {
+            EnemyKind::Forgetter => (1.0, 2.0),
+            EnemyKind::MindWorker => (1.4, 2.4),
+        }
+    }
+}
+
+// Enemigo tal como lo describe la cabecera del nivel: dónde aparece y por
+// qué celdas patrulla (la primera es la de aparición)
+#[derive(Clone, Debug)]
+pub struct EnemySpawn {
+    pub kind: EnemyKind,
+    pub route: Vec<(usize, usize)>,
+}
+
+pub struct Enemy {
+    pub kind: EnemyKind,
+    pub pos: Vector2,               // coordenadas del mundo
+    pub chasing: bool,
+    route: Vec<(usize, usize)>,
+    next_waypoint: usize,
+    path: Vec<(usize, usize)>,      // celdas que faltan recorrer, sin la actual
+    repath: f32,                    // segundos hasta poder recalcular el camino
+}
+
+impl Enemy {
+    pub fn new(spawn: &EnemySpawn, block_size: f32) -> Self {
+        let (i, j) = spawn.route[0];
+        Enemy {
+            kind: spawn.kind,
+            pos: cell_center((i, j), block_size),
+            chasing: false,
+            route: spawn.route.clone(),
+            next_waypoint: 1 % spawn.route.len(),
+            path: Vec::new(),
+            repath: 0.0,
+        }
+    }
+
+    pub fn sprite(&self) -> Sprite {
+        Sprite { pos: self.pos, texture: self.kind.texture(), scale: 0.8, offset: 0.0 }
+    }
+
+    // Si el círculo del enemigo toca el del jugador
+    pub fn touches(&self, pos: Vector2, radius: f32, block_size: f32) -> bool {
+        self.pos.distance_to(pos) < radius + ENEMY_RADIUS * block_size
+    }
+
+    // Avanza la IA `dt` segundos. Patrulla su ruta hasta ver a `target`; lo
+    // persigue con A* mientras lo vea y, si lo pierde, revisa el último lugar
+    // donde lo vio antes de volver a patrullar.
+    pub fn update(&mut self, maze: &Maze, doors: &Doors, target: Vector2, block_size: f32, dt: f32) {
+        self.repath -= dt;
+
+        let sees = self.pos.distance_to(target) <= SIGHT_RANGE * block_size
+            && has_line_of_sight(maze, doors, self.pos, target, block_size as usize);
+
+        if sees {
+            if !self.chasing || self.repath <= 0.0 {
+                let (i, j) = world_to_cell(target, block_size);
+                self.path = self.find_path(maze, doors, (i.max(0) as usize, j.max(0) as usize), block_size);
+                self.repath = REPATH_INTERVAL;
+            }
+            self.chasing = true;
+        } else if self.chasing && self.path.is_empty() {
+            self.chasing = false;
+        }
+
+        // Una puerta que se cerró en el camino obliga a buscar otro
+        if self.path.first().is_some_and(|&(i, j)| !passable(maze, doors, i, j)) {
+            self.path.clear();
+        }
+
+        if !self.chasing && self.path.is_empty() && self.repath <= 0.0 {
+            let waypoint = self.route[self.next_waypoint];
+            self.next_waypoint = (self.next_waypoint + 1) % self.route.len();
+            self.path = self.find_path(maze, doors, waypoint, block_size);
+            self.repath = REPATH_INTERVAL;
+        }
+
+        // A la próxima celda del camino, o directo al jugador si ya está en su celda
+        let goal = match self.path.first() {
+            Some(&cell) => cell_center(cell, block_size),
+            None if sees => target,
+            None => return,
+        };
+
+        let (patrol_speed, chase_speed) = self.kind.speeds();
+        let speed = if self.chasing { chase_speed } else { patrol_speed } * block_size;
+        let offset = goal - self.pos;
+        let step = speed * dt;
+
+        if offset.length() <= step {
+            self.pos = goal;
+            if !self.path.is_empty() {
+                self.path.remove(0);
+            }
+        } else {
+            let motion = offset.normalized() * step;
+            self.pos = move_and_slide(maze, doors, self.pos, motion, ENEMY_RADIUS * block_size, block_size);
+        }
+    }
+
+    // Camino desde la celda actual hasta `goal`, sin la celda actual
+    fn find_path(&self, maze: &Maze, doors: &Doors, goal: (usize, usize), block_size: f32) -> Vec<(usize, usize)> {
+        let (i, j) = world_to_cell(self.pos, block_size);
+        let start = (i.max(0) as usize, j.max(0) as usize);
+        let mut path = astar(maze, start, goal, |i, j| passable(maze, doors, i, j)).unwrap_or_default();
+        if !path.is_empty() {
+            path.remove(0);
+        }
+        path
+    }
+}
+
+// Enemigos del nivel en su punto de aparición
+pub fn spawn_enemies(level: &Level, block_size: usize) -> Vec<Enemy> {
+    level.enemies.iter().map(|spawn| Enemy::new(spawn, block_size as f32)).collect()
+}
+
+// Los enemigos no atraviesan puertas que no estén abiertas del todo
+fn passable(maze: &Maze, doors: &Doors, i: usize, j: usize) -> bool {
+    let (i, j) = (i as isize, j as isize);
+    let cell = cell_at(maze, i, j);
+    if cell == Some(DOOR) {
+        return doors.get(i, j).is_some_and(|door| door.open >= 1.0);
+    }
+    is_walkable(cell)
+}
+
+fn cell_center((i, j): (usize, usize), block_size: f32) -> Vector2 {
+    Vector2::new((i as f32 + 0.5) * block_size, (j as f32 + 0.5) * block_size)
+}
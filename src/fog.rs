@@ -0,0 +1,90 @@
+// fog.rs
+
+use raylib::prelude::Vector2;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::caster::grid_cells;
+use crate::maze::{Maze, maze_width};
+
+// Celdas que el jugador ya vio. Los hilos del render marcan las celdas que
+// cruza cada rayo, por eso cada celda es atómica.
+pub struct Explored {
+    width: usize,
+    height: usize,
+    cells: Vec<AtomicBool>, // por filas, de arriba hacia abajo
+}
+
+impl Explored {
+    // Nada explorado todavía
+    pub fn new(maze: &Maze) -> Self {
+        let width = maze_width(maze);
+        let height = maze.len();
+        Explored {
+            width,
+            height,
+            cells: (0..width * height).map(|_| AtomicBool::new(false)).collect(),
+        }
+    }
+
+    pub fn mark(&self, i: isize, j: isize) {
+        // Casi siempre la celda ya estaba marcada: leer primero evita que los
+        // hilos del render se peleen por escribir las mismas líneas de caché
+        if let Some(cell) = self.index(i, j).map(|index| &self.cells[index]) {
+            if !cell.load(Ordering::Relaxed) {
+                cell.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    // Marca todas las celdas que cruza el segmento, con el mismo recorrido
+    // de la cuadrícula que `march` para no saltearse las que solo toca en una esquina
+    pub fn mark_ray(&self, from: Vector2, to: Vector2, block_size: f32) {
+        let cells = grid_cells(from, (to.y - from.y).atan2(to.x - from.x), block_size);
+        let starts_inside = self.index(cells.i, cells.j).is_some();
+        self.mark(cells.i, cells.j);
+
+        let length = from.distance_to(to);
+        for (i, j, _, _) in cells.take_while(|&(_, _, distance, _)| distance <= length) {
+            // Un segmento que sale del rectángulo del maze ya no vuelve a entrar
+            if starts_inside && self.index(i, j).is_none() {
+                return;
+            }
+            self.mark(i, j);
+        }
+    }
+
+    pub fn is_explored(&self, i: isize, j: isize) -> bool {
+        self.index(i, j).is_some_and(|index| self.cells[index].load(Ordering::Relaxed))
+    }
+
+    // Una línea por fila: '#' explorada, '.' sin explorar
+    pub fn to_rows(&self) -> Vec<String> {
+        (0..self.height)
+            .map(|j| {
+                (0..self.width)
+                    .map(|i| if self.is_explored(i as isize, j as isize) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Lo inverso de `to_rows` para un maze dado; lo que no entra se ignora
+    pub fn from_rows(maze: &Maze, rows: &[String]) -> Self {
+        let explored = Explored::new(maze);
+        for (j, row) in rows.iter().enumerate() {
+            for (i, ch) in row.chars().enumerate() {
+                if ch == '#' {
+                    explored.mark(i as isize, j as isize);
+                }
+            }
+        }
+        explored
+    }
+
+    fn index(&self, i: isize, j: isize) -> Option<usize> {
+        if i < 0 || j < 0 || i as usize >= self.width || j as usize >= self.height {
+            return None;
+        }
+        Some(j as usize * self.width + i as usize)
+    }
+}
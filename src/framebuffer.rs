@@ -1,34 +1,89 @@
 // framebuffer.rs
 
 use raylib::prelude::*;
+use std::fs;
+use std::ops::{Index, IndexMut};
+use std::slice;
+use std::thread;
 
 pub struct Framebuffer {
     pub width: u32,
     pub height: u32,
-    pub color_buffer: Image,
+    pub color_buffer: Vec<Color>, // píxeles por filas, de arriba hacia abajo
+    pub depth_buffer: Vec<f32>,   // distancia perpendicular a la pared de cada columna
     background_color: Color,
     current_color: Color,
+    texture: Option<Texture2D>,   // textura de GPU reutilizada entre cuadros
+    threads: usize,               // hilos que usa render_columns
+}
+
+// Una columna de la pantalla dentro del tramo de filas de un hilo. Los
+// píxeles están por filas, así que cada píxel de la columna está en otra fila.
+pub struct Column<'a, 'b> {
+    rows: &'a mut [&'b mut [Color]],
+    x: usize, // posición de la columna dentro del tramo
+}
+
+impl Column<'_, '_> {
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl Index<usize> for Column<'_, '_> {
+    type Output = Color;
+
+    fn index(&self, y: usize) -> &Color {
+        &self.rows[y][self.x]
+    }
+}
+
+impl IndexMut<usize> for Column<'_, '_> {
+    fn index_mut(&mut self, y: usize) -> &mut Color {
+        &mut self.rows[y][self.x]
+    }
 }
 
 impl Framebuffer {
     pub fn new(width: u32, height: u32) -> Self {
-        let color_buffer = Image::gen_image_color(width as i32, height as i32, Color::BLACK);
         Framebuffer {
             width,
             height,
-            color_buffer,
+            color_buffer: vec![Color::BLACK; (width * height) as usize],
+            depth_buffer: vec![f32::INFINITY; width as usize],
             background_color: Color::BLACK,
             current_color: Color::WHITE,
+            texture: None,
+            threads: 1,
         }
     }
 
+    // Cambia la resolución interna; la textura de GPU se vuelve a crear en el próximo swap
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.color_buffer = vec![self.background_color; (width * height) as usize];
+        self.depth_buffer = vec![f32::INFINITY; width as usize];
+        self.texture = None;
+    }
+
     pub fn clear(&mut self) {
-        self.color_buffer = Image::gen_image_color(self.width as i32, self.height as i32, self.background_color);
+        self.color_buffer.fill(self.background_color);
+        self.depth_buffer.fill(f32::INFINITY);
+    }
+
+    // Distancia a la pared más cercana en la columna x; infinita si no hay pared
+    pub fn depth_at(&self, x: u32) -> f32 {
+        self.depth_buffer.get(x as usize).copied().unwrap_or(f32::INFINITY)
     }
 
     pub fn set_pixel(&mut self, x: u32, y: u32) {
         if x < self.width && y < self.height {
-            self.color_buffer.draw_pixel(x as i32, y as i32, self.current_color);
+            self.color_buffer[(y * self.width + x) as usize] = self.current_color;
         }
     }
 
@@ -40,18 +95,99 @@ impl Framebuffer {
         self.current_color = color;
     }
 
-    pub fn _render_to_file(&self, file_path: &str) {
-        self.color_buffer.export_image(file_path);
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    // Dibuja la pantalla columna por columna repartiendo las columnas entre
+    // los hilos configurados. Cada hilo se queda con un tramo de columnas
+    // seguidas y escribe directo en su pedazo de cada fila del framebuffer.
+    // `render` devuelve la profundidad de la columna, que queda en el depth buffer.
+    pub fn render_columns<F>(&mut self, render: F)
+    where
+        F: Fn(u32, &mut Column) -> f32 + Sync,
+    {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // El tramo `t` de cada fila le toca al hilo `t`
+        let per_thread = width.div_ceil(self.threads.min(width));
+        let mut strips: Vec<Vec<&mut [Color]>> = Vec::new();
+        for row in self.color_buffer.chunks_mut(width) {
+            for (t, segment) in row.chunks_mut(per_thread).enumerate() {
+                if strips.len() <= t {
+                    strips.push(Vec::with_capacity(height));
+                }
+                strips[t].push(segment);
+            }
+        }
+
+        let render = &render;
+        thread::scope(|scope| {
+            for (chunk_index, (mut rows, depths)) in strips.into_iter().zip(self.depth_buffer.chunks_mut(per_thread)).enumerate() {
+                scope.spawn(move || {
+                    for (offset, depth) in depths.iter_mut().enumerate() {
+                        let mut column = Column { rows: &mut rows, x: offset };
+                        *depth = render((chunk_index * per_thread + offset) as u32, &mut column);
+                    }
+                });
+            }
+        });
+    }
+
+    fn to_image(&self) -> Image {
+        let image = Image::gen_image_color(self.width as i32, self.height as i32, Color::BLACK);
+        let bytes = as_bytes(&self.color_buffer);
+        unsafe {
+            slice::from_raw_parts_mut(image.data as *mut u8, bytes.len()).copy_from_slice(bytes);
+        }
+        image
+    }
+
+    // Guarda el cuadro actual: .ppm se escribe a mano, el resto lo exporta raylib (png, bmp...)
+    pub fn render_to_file(&self, file_path: &str) -> Result<(), String> {
+        if file_path.to_lowercase().ends_with(".ppm") {
+            let mut data = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+            for color in &self.color_buffer {
+                data.extend_from_slice(&[color.r, color.g, color.b]);
+            }
+            return fs::write(file_path, data).map_err(|e| format!("{}: {}", file_path, e));
+        }
+
+        if self.to_image().export_image(file_path) {
+            Ok(())
+        } else {
+            Err(format!("{}: no se pudo exportar la imagen", file_path))
+        }
     }
 
     pub fn swap_buffers(
-        &self,
+        &mut self,
         window: &mut RaylibHandle,
         raylib_thread: &RaylibThread,
     ) {
-        if let Ok(texture) = window.load_texture_from_image(raylib_thread, &self.color_buffer) {
-            let mut renderer = window.begin_drawing(raylib_thread);
-            renderer.draw_texture(&texture, 0, 0, Color::WHITE);
+        // La textura se crea una sola vez y después solo se actualizan sus píxeles
+        if self.texture.is_none() {
+            self.texture = window.load_texture_from_image(raylib_thread, &self.to_image()).ok();
+        } else if let Some(texture) = self.texture.as_mut() {
+            let _ = texture.update_texture(as_bytes(&self.color_buffer));
+        }
+
+        // Se estira a toda la ventana: con escala de render menor a 100% la imagen se agranda
+        let screen = Rectangle::new(0.0, 0.0, window.get_screen_width() as f32, window.get_screen_height() as f32);
+        let source = Rectangle::new(0.0, 0.0, self.width as f32, self.height as f32);
+        let mut renderer = window.begin_drawing(raylib_thread);
+        if let Some(texture) = &self.texture {
+            renderer.draw_texture_pro(texture, source, screen, Vector2::zero(), 0.0, Color::WHITE);
         }
     }
-}
\ No newline at end of file
+}
+
+// Los píxeles como bytes RGBA, el formato que esperan las texturas de raylib
+fn as_bytes(pixels: &[Color]) -> &[u8] {
+    // Color es #[repr(C)] con cuatro u8: r, g, b, a
+    unsafe { slice::from_raw_parts(pixels.as_ptr() as *const u8, pixels.len() * 4) }
+}
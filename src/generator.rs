@@ -0,0 +1,359 @@
+// generator.rs
+
+use std::collections::VecDeque;
+
+use crate::maze::{Maze, WALL_CELLS, cell_at, neighbors};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Algorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Eller,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [
+        Algorithm::RecursiveBacktracker,
+        Algorithm::Prim,
+        Algorithm::Kruskal,
+        Algorithm::Eller,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::RecursiveBacktracker => "backtracker",
+            Algorithm::Prim => "Prim",
+            Algorithm::Kruskal => "Kruskal",
+            Algorithm::Eller => "Eller",
+        }
+    }
+}
+
+// Parámetros de generación. `width` y `height` son celdas de pasillo: la
+// cuadrícula resultante mide (2 * width + 1) x (2 * height + 1)
+#[derive(Clone, Copy, Debug)]
+pub struct GenConfig {
+    pub algorithm: Algorithm,
+    pub seed: u64,
+    pub width: usize,
+    pub height: usize,
+    pub braid: f32, // fracción de callejones sin salida que se abren (0.0 a 1.0)
+}
+
+// Genera un maze perfecto con el algoritmo pedido, le agrega ciclos según
+// `braid` y pone la salida en la celda más lejana a la aparición (1, 1)
+pub fn generate(config: &GenConfig) -> Maze {
+    let width = config.width.max(1);
+    let height = config.height.max(1);
+    let mut rng = Rng::new(config.seed);
+    let mut grid = Grid::new(width, height);
+
+    match config.algorithm {
+        Algorithm::RecursiveBacktracker => backtracker(&mut grid, &mut rng),
+        Algorithm::Prim => prim(&mut grid, &mut rng),
+        Algorithm::Kruskal => kruskal(&mut grid, &mut rng),
+        Algorithm::Eller => eller(&mut grid, &mut rng),
+    }
+
+    braid(&mut grid, &mut rng, config.braid);
+
+    let mut maze = grid.cells;
+    let (gi, gj) = farthest_cell(&maze, (1, 1));
+    maze[gj][gi] = 'g';
+    maze
+}
+
+// Cuadrícula de caracteres donde la celda lógica (x, y) vive en (2x + 1, 2y + 1)
+struct Grid {
+    width: usize,
+    height: usize,
+    cells: Maze,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Self {
+        // Paredes con la paleta de colores repetida en diagonal, como en los niveles a mano
+        let cells = (0..2 * height + 1)
+            .map(|j| (0..2 * width + 1).map(|i| WALL_CELLS[(i + j) % WALL_CELLS.len()]).collect())
+            .collect();
+
+        let mut grid = Grid { width, height, cells };
+        for y in 0..height {
+            for x in 0..width {
+                grid.cells[2 * y + 1][2 * x + 1] = ' ';
+            }
+        }
+        grid
+    }
+
+    fn carve(&mut self, (x, y): (usize, usize), (nx, ny): (usize, usize)) {
+        self.cells[y + ny + 1][x + nx + 1] = ' ';
+    }
+
+    fn is_open(&self, (x, y): (usize, usize), (nx, ny): (usize, usize)) -> bool {
+        self.cells[y + ny + 1][x + nx + 1] == ' '
+    }
+
+    // Celdas lógicas vecinas dentro de la cuadrícula
+    fn adjacent(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+        let mut result = Vec::with_capacity(4);
+        if x > 0 { result.push((x - 1, y)); }
+        if y > 0 { result.push((x, y - 1)); }
+        if x + 1 < self.width { result.push((x + 1, y)); }
+        if y + 1 < self.height { result.push((x, y + 1)); }
+        result
+    }
+}
+
+fn backtracker(grid: &mut Grid, rng: &mut Rng) {
+    let mut visited = vec![vec![false; grid.width]; grid.height];
+    let mut stack = vec![(0, 0)];
+    visited[0][0] = true;
+
+    while let Some(&current) = stack.last() {
+        let options: Vec<(usize, usize)> = grid
+            .adjacent(current)
+            .into_iter()
+            .filter(|&(x, y)| !visited[y][x])
+            .collect();
+
+        if options.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let next = options[rng.below(options.len())];
+        grid.carve(current, next);
+        visited[next.1][next.0] = true;
+        stack.push(next);
+    }
+}
+
+fn prim(grid: &mut Grid, rng: &mut Rng) {
+    let mut in_maze = vec![vec![false; grid.width]; grid.height];
+    let mut frontier = Vec::new();
+
+    in_maze[0][0] = true;
+    for next in grid.adjacent((0, 0)) {
+        frontier.push(((0, 0), next));
+    }
+
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(rng.below(frontier.len()));
+        if in_maze[to.1][to.0] {
+            continue;
+        }
+
+        grid.carve(from, to);
+        in_maze[to.1][to.0] = true;
+        for next in grid.adjacent(to) {
+            if !in_maze[next.1][next.0] {
+                frontier.push((to, next));
+            }
+        }
+    }
+}
+
+fn kruskal(grid: &mut Grid, rng: &mut Rng) {
+    let width = grid.width;
+    let index = |(x, y): (usize, usize)| y * width + x;
+    let mut sets = DisjointSet::new(grid.width * grid.height);
+
+    let mut edges = Vec::new();
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            if x + 1 < grid.width { edges.push(((x, y), (x + 1, y))); }
+            if y + 1 < grid.height { edges.push(((x, y), (x, y + 1))); }
+        }
+    }
+    rng.shuffle(&mut edges);
+
+    for (a, b) in edges {
+        if sets.union(index(a), index(b)) {
+            grid.carve(a, b);
+        }
+    }
+}
+
+fn eller(grid: &mut Grid, rng: &mut Rng) {
+    let width = grid.width;
+    let mut sets: Vec<usize> = (0..width).collect();
+    let mut next_set = width;
+
+    for y in 0..grid.height {
+        let last_row = y + 1 == grid.height;
+
+        // Unir celdas vecinas de conjuntos distintos (en la última fila, todas)
+        for x in 0..width.saturating_sub(1) {
+            if sets[x] != sets[x + 1] && (last_row || rng.chance(0.5)) {
+                grid.carve((x, y), (x + 1, y));
+                let (keep, merged) = (sets[x], sets[x + 1]);
+                for set in sets.iter_mut() {
+                    if *set == merged {
+                        *set = keep;
+                    }
+                }
+            }
+        }
+
+        if last_row {
+            break;
+        }
+
+        // Cada conjunto baja al menos una vez a la fila siguiente
+        let mut below = vec![None; width];
+        let mut members: Vec<usize> = (0..width).collect();
+        rng.shuffle(&mut members);
+        for &x in &members {
+            let first_of_set = !below.iter().flatten().any(|&set| set == sets[x]);
+            if first_of_set || rng.chance(0.3) {
+                grid.carve((x, y), (x, y + 1));
+                below[x] = Some(sets[x]);
+            }
+        }
+
+        sets = below
+            .into_iter()
+            .map(|set| set.unwrap_or_else(|| {
+                next_set += 1;
+                next_set
+            }))
+            .collect();
+    }
+}
+
+// Abre callejones sin salida para crear ciclos
+fn braid(grid: &mut Grid, rng: &mut Rng, factor: f32) {
+    if factor <= 0.0 {
+        return;
+    }
+
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            let adjacent = grid.adjacent((x, y));
+            let open = adjacent.iter().filter(|&&next| grid.is_open((x, y), next)).count();
+            if open != 1 || !rng.chance(factor) {
+                continue;
+            }
+
+            let closed: Vec<(usize, usize)> = adjacent
+                .into_iter()
+                .filter(|&next| !grid.is_open((x, y), next))
+                .collect();
+            if !closed.is_empty() {
+                let next = closed[rng.below(closed.len())];
+                grid.carve((x, y), next);
+            }
+        }
+    }
+}
+
+// Celda vacía más lejana (en pasos) desde `start`
+fn farthest_cell(maze: &Maze, start: (usize, usize)) -> (usize, usize) {
+    let mut distance: Vec<Vec<Option<usize>>> = maze.iter().map(|row| vec![None; row.len()]).collect();
+    let mut queue = VecDeque::from([start]);
+    let mut farthest = start;
+    distance[start.1][start.0] = Some(0);
+
+    while let Some((i, j)) = queue.pop_front() {
+        farthest = (i, j);
+        let steps = distance[j][i].unwrap_or(0);
+        for (ni, nj) in neighbors(i, j) {
+            if cell_at(maze, ni, nj) == Some(' ') && distance[nj as usize][ni as usize].is_none() {
+                distance[nj as usize][ni as usize] = Some(steps + 1);
+                queue.push_back((ni as usize, nj as usize));
+            }
+        }
+    }
+
+    farthest
+}
+
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        DisjointSet { parent: (0..size).collect() }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    // Une los conjuntos; false si ya estaban unidos
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return false;
+        }
+        self.parent[rb] = ra;
+        true
+    }
+}
+
+// Generador pseudoaleatorio SplitMix64: reproducible con la misma semilla
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Entero en 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    // true con probabilidad p
+    pub fn chance(&mut self, p: f32) -> bool {
+        ((self.next_u64() >> 40) as f32 / (1u64 << 24) as f32) < p
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Level;
+    use crate::validate::validate;
+
+    // Todo maze generado tiene que pasar la validación: salida alcanzable,
+    // bordes cerrados y sin zonas muertas
+    #[test]
+    fn generated_mazes_are_valid() {
+        for algorithm in Algorithm::ALL {
+            for seed in 0..50 {
+                let config = GenConfig {
+                    algorithm,
+                    seed,
+                    width: 3 + seed as usize % 12,
+                    height: 2 + seed as usize % 9,
+                    braid: (seed % 3) as f32 * 0.5,
+                };
+                let report = validate(&Level::from_maze("generado", generate(&config)));
+                let errors: Vec<String> = report.errors.iter().map(|e| e.to_string()).collect();
+                assert!(report.is_ok(), "{:?} semilla {}: {:?}", algorithm, seed, errors);
+            }
+        }
+    }
+}
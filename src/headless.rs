@@ -0,0 +1,205 @@
+// headless.rs
+
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use std::thread;
+
+use crate::caster::RayConfig;
+use crate::framebuffer::Framebuffer;
+use crate::maze::load_maze;
+use crate::player::Player;
+use crate::solver::{Hint, key_order};
+use crate::sprite::render_sprites;
+use crate::textures::TextureManager;
+use crate::door::Doors;
+use crate::enemy::spawn_enemies;
+use crate::fog::Explored;
+use crate::key::{Inventory, key_sprites};
+use crate::{Scene, render_keys, render_maze, render_minimap, render_world};
+
+const USAGE: &str = "uso: --headless <nivel> [--pos x,y] [--angle grados] [--mode 2d|3d] \
+[--size AnchoxAlto] [--frames n] [--spin grados] [--hint] [--reveal] [--threads n] [--out archivo.png|archivo.ppm]";
+
+// Opciones de la línea de comandos para renderizar sin ventana
+struct Options {
+    level: String,
+    pos: Option<(f32, f32)>, // en celdas, por ejemplo 1.5,1.5 es el centro de la celda (1, 1)
+    angle: Option<f32>,      // en grados
+    mode_3d: bool,
+    width: u32,
+    height: u32,
+    frames: usize,
+    spin: f32,               // grados que gira la cámara entre cuadros
+    hint: bool,
+    reveal: bool,            // minimapa completo en lugar de solo lo visto en los cuadros anteriores
+    threads: usize,
+    out: String,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        level: String::new(),
+        pos: None,
+        angle: None,
+        mode_3d: true,
+        width: 1300,
+        height: 900,
+        frames: 1,
+        spin: 0.0,
+        hint: false,
+        reveal: false,
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        out: "frame.png".to_string(),
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("falta el valor de {}", arg));
+
+        match arg.as_str() {
+            "--pos" => {
+                let text = value()?;
+                let (x, y) = text.split_once(',').ok_or_else(|| format!("--pos espera x,y: '{}'", text))?;
+                options.pos = Some((parse_number(x)?, parse_number(y)?));
+            }
+            "--angle" => options.angle = Some(parse_number(value()?)?),
+            "--mode" => {
+                options.mode_3d = match value()?.to_lowercase().as_str() {
+                    "3d" => true,
+                    "2d" => false,
+                    other => return Err(format!("--mode debe ser 2d o 3d: '{}'", other)),
+                };
+            }
+            "--size" => {
+                let text = value()?;
+                let (w, h) = text.split_once('x').ok_or_else(|| format!("--size espera AnchoxAlto: '{}'", text))?;
+                options.width = w.trim().parse().map_err(|_| format!("ancho inválido: '{}'", w))?;
+                options.height = h.trim().parse().map_err(|_| format!("alto inválido: '{}'", h))?;
+            }
+            "--frames" => {
+                let text = value()?;
+                options.frames = text.parse().map_err(|_| format!("--frames espera un entero: '{}'", text))?;
+            }
+            "--spin" => options.spin = parse_number(value()?)?,
+            "--hint" => options.hint = true,
+            "--reveal" => options.reveal = true,
+            "--threads" => {
+                let text = value()?;
+                options.threads = text.parse().map_err(|_| format!("--threads espera un entero: '{}'", text))?;
+            }
+            "--out" => options.out = value()?.clone(),
+            other if other.starts_with("--") => return Err(format!("opción desconocida '{}'", other)),
+            other if options.level.is_empty() => options.level = other.to_string(),
+            other => return Err(format!("argumento de más '{}'", other)),
+        }
+    }
+
+    if options.level.is_empty() {
+        return Err("falta el archivo del nivel".to_string());
+    }
+    if options.width == 0 || options.height == 0 || options.frames == 0 || options.threads == 0 {
+        return Err("el tamaño, los cuadros y los hilos deben ser mayores que cero".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_number(text: &str) -> Result<f32, String> {
+    text.trim().parse().map_err(|_| format!("no es un número: '{}'", text))
+}
+
+// Nombre del cuadro `index`: con varios cuadros se numeran (frame_000.png, frame_001.png...)
+fn frame_path(out: &str, index: usize, frames: usize) -> String {
+    if frames == 1 {
+        return out.to_string();
+    }
+    match out.rsplit_once('.') {
+        Some((stem, extension)) => format!("{}_{:03}.{}", stem, index, extension),
+        None => format!("{}_{:03}", out, index),
+    }
+}
+
+// Renderiza un nivel sin abrir ventana ni dispositivo de audio y guarda los cuadros.
+// Pensado para CI: compara las imágenes con las de referencia.
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let level = match load_maze(&options.level) {
+        Ok(level) => level,
+        Err(e) => {
+            eprintln!("❌ {}: {}", options.level, e);
+            return 1;
+        }
+    };
+
+    let block_size = 100;
+    let ray_config = RayConfig {
+        max_distance: 50.0 * block_size as f32,
+        void: level.void,
+    };
+    let texture_manager = TextureManager::headless(&level.palette);
+
+    let pos = match options.pos {
+        Some((x, y)) => Vector2::new(x * block_size as f32, y * block_size as f32),
+        None => level.spawn_position(block_size),
+    };
+    let mut player = Player {
+        pos,
+        a: options.angle.map_or(level.spawn_angle, f32::to_radians),
+        fov: PI / 3.0,
+        radius: level.player_radius * block_size as f32,
+        inventory: Inventory::default(),
+    };
+
+    let mut sprites = level.world_sprites(block_size);
+    sprites.extend(key_sprites(&level.keys, block_size));
+    let enemies = spawn_enemies(&level, block_size);
+    sprites.extend(enemies.iter().map(|enemy| enemy.sprite()));
+    let doors = Doors::new(&level);
+    let explored = Explored::new(&level.maze);
+
+    let mut framebuffer = Framebuffer::new(options.width, options.height);
+    framebuffer.set_background_color(Color::new(153, 102, 204, 255));
+    framebuffer.set_threads(options.threads);
+
+    for index in 0..options.frames {
+        let cell = player.grid_cell(block_size as f32);
+        let hint = if options.hint && cell.0 >= 0 && cell.1 >= 0 {
+            let start = (cell.0 as usize, cell.1 as usize);
+            match key_order(&level).first() {
+                Some(&(_, key_cell)) => Hint::to_cell(&level, &player.inventory, start, key_cell),
+                None => Hint::new(&level, &player.inventory, start),
+            }
+        } else {
+            None
+        };
+
+        framebuffer.clear();
+        if options.mode_3d {
+            let scene = Scene { level: &level, doors: &doors, hint: hint.as_ref(), explored: &explored };
+            render_world(&mut framebuffer, &scene, block_size, &player, &texture_manager, &ray_config);
+            render_sprites(&mut framebuffer, &player, &sprites, &texture_manager, block_size);
+            let fog = (!options.reveal).then_some(&explored);
+            render_minimap(&mut framebuffer, &level.maze, &player, &enemies, block_size, hint.as_ref(), fog);
+        } else {
+            render_maze(&mut framebuffer, &level.maze, &doors, block_size, &player, &enemies, &ray_config);
+        }
+        render_keys(&mut framebuffer, &level, &player.inventory, None);
+
+        let path = frame_path(&options.out, index, options.frames);
+        if let Err(e) = framebuffer.render_to_file(&path) {
+            eprintln!("❌ {}", e);
+            return 1;
+        }
+        println!("✓ {}", path);
+
+        player.a = (player.a + options.spin.to_radians()).rem_euclid(2.0 * PI);
+    }
+
+    0
+}
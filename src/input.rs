@@ -0,0 +1,417 @@
+// input.rs
+
+use raylib::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+pub const CONFIG_FILE: &str = "controls.cfg";
+
+// Acciones lógicas del juego; las teclas se asignan en el archivo de configuración
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveForward,
+    MoveBackward,
+    StrafeLeft,
+    StrafeRight,
+    TurnLeft,
+    TurnRight,
+    Use,
+    ToggleMap,
+    ToggleHint,
+    RenderScale,
+    RevealMap,
+    Pause,
+    Select,
+    Back,
+    Level1,
+    Level2,
+    Level3,
+    Endless,
+    Continue,
+    ToggleControls,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 21] = [
+        Action::MoveForward,
+        Action::MoveBackward,
+        Action::StrafeLeft,
+        Action::StrafeRight,
+        Action::TurnLeft,
+        Action::TurnRight,
+        Action::Use,
+        Action::ToggleMap,
+        Action::ToggleHint,
+        Action::RenderScale,
+        Action::RevealMap,
+        Action::Pause,
+        Action::Select,
+        Action::Back,
+        Action::Level1,
+        Action::Level2,
+        Action::Level3,
+        Action::Endless,
+        Action::Continue,
+        Action::ToggleControls,
+        Action::Quit,
+    ];
+
+    // Nombre usado en el archivo de configuración
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Action::MoveForward => "move_forward",
+            Action::MoveBackward => "move_backward",
+            Action::StrafeLeft => "strafe_left",
+            Action::StrafeRight => "strafe_right",
+            Action::TurnLeft => "turn_left",
+            Action::TurnRight => "turn_right",
+            Action::Use => "use",
+            Action::ToggleMap => "toggle_map",
+            Action::ToggleHint => "toggle_hint",
+            Action::RenderScale => "render_scale",
+            Action::RevealMap => "reveal_map",
+            Action::Pause => "pause",
+            Action::Select => "select",
+            Action::Back => "back",
+            Action::Level1 => "level_1",
+            Action::Level2 => "level_2",
+            Action::Level3 => "level_3",
+            Action::Endless => "endless",
+            Action::Continue => "continue",
+            Action::ToggleControls => "toggle_controls",
+            Action::Quit => "quit",
+        }
+    }
+
+    fn default_bindings(self) -> Vec<Binding> {
+        use KeyboardKey::*;
+        let keys = match self {
+            Action::MoveForward => vec![KEY_W, KEY_UP],
+            Action::MoveBackward => vec![KEY_S, KEY_DOWN],
+            Action::StrafeLeft => vec![KEY_A],
+            Action::StrafeRight => vec![KEY_D],
+            Action::TurnLeft => vec![KEY_LEFT],
+            Action::TurnRight => vec![KEY_RIGHT],
+            Action::Use => vec![KEY_E, KEY_SPACE],
+            Action::ToggleMap => vec![KEY_M],
+            Action::ToggleHint => vec![KEY_H],
+            Action::RenderScale => vec![KEY_F2],
+            Action::RevealMap => vec![KEY_F3],
+            Action::Pause => vec![KEY_P],
+            Action::Select => vec![KEY_ENTER],
+            Action::Back => vec![KEY_BACKSPACE],
+            Action::Level1 => vec![KEY_ONE],
+            Action::Level2 => vec![KEY_TWO],
+            Action::Level3 => vec![KEY_THREE],
+            Action::Endless => vec![KEY_FOUR],
+            Action::Continue => vec![KEY_FIVE],
+            Action::ToggleControls => vec![KEY_C],
+            Action::Quit => vec![KEY_ESCAPE],
+        };
+        keys.into_iter().map(Binding::Key).collect()
+    }
+}
+
+// Esquema de controles elegido en el menú
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ControlScheme {
+    Tank,   // clásico: los desplazamientos laterales giran, sin mouse
+    Modern, // FPS: desplazamiento lateral, flechas o mouse giran
+}
+
+impl ControlScheme {
+    pub fn toggle(self) -> Self {
+        match self {
+            ControlScheme::Tank => ControlScheme::Modern,
+            ControlScheme::Modern => ControlScheme::Tank,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ControlScheme::Tank => "Clásico (tanque)",
+            ControlScheme::Modern => "Moderno (FPS)",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Binding {
+    Key(KeyboardKey),
+    Mouse(MouseButton),
+}
+
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<Binding>>,
+    pub controls: ControlScheme,
+    pub mouse_sensitivity: f32, // radianes por píxel de mouse
+    pub invert_mouse: bool,
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        InputMap {
+            bindings: Action::ALL.iter().map(|&action| (action, action.default_bindings())).collect(),
+            controls: ControlScheme::Modern,
+            mouse_sensitivity: 0.003,
+            invert_mouse: false,
+        }
+    }
+
+    // Lee el archivo de configuración; lo que falte o esté mal queda con su valor por defecto
+    pub fn load(path: &str) -> Self {
+        let mut input = InputMap::new();
+
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => {
+                // Primera ejecución: se escribe el archivo para que se pueda editar
+                if let Err(e) = input.save(path) {
+                    println!("⚠ No se pudo crear '{}': {}", path, e);
+                }
+                return input;
+            }
+        };
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Err(message) = input.apply_line(line) {
+                println!("⚠ {} línea {}: {}", path, index + 1, message);
+            }
+        }
+
+        input
+    }
+
+    fn apply_line(&mut self, line: &str) -> Result<(), String> {
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("se esperaba 'acción = teclas', se encontró '{}'", line))?;
+        let (key, value) = (key.trim(), value.trim());
+
+        match key {
+            "controls" => {
+                self.controls = match value {
+                    "modern" => ControlScheme::Modern,
+                    "tank" => ControlScheme::Tank,
+                    _ => return Err(format!("controls debe ser 'modern' o 'tank', no '{}'", value)),
+                };
+            }
+            "mouse_sensitivity" => {
+                self.mouse_sensitivity = value
+                    .parse()
+                    .map_err(|_| format!("mouse_sensitivity no es un número: '{}'", value))?;
+            }
+            "invert_mouse" => {
+                self.invert_mouse = value
+                    .parse()
+                    .map_err(|_| format!("invert_mouse debe ser true o false: '{}'", value))?;
+            }
+            _ => {
+                let action = Action::ALL
+                    .iter()
+                    .copied()
+                    .find(|action| action.config_name() == key)
+                    .ok_or_else(|| format!("acción desconocida '{}'", key))?;
+                let bindings = value
+                    .split(',')
+                    .filter(|name| !name.trim().is_empty())
+                    .map(|name| parse_binding(name.trim()).ok_or_else(|| format!("tecla desconocida '{}'", name.trim())))
+                    .collect::<Result<Vec<Binding>, String>>()?;
+                self.bindings.insert(action, bindings);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut text = String::from("# Inside Out Maze - controles\n# acción = tecla, tecla...\n");
+        text.push_str(&format!(
+            "controls = {}\n",
+            if self.controls == ControlScheme::Modern { "modern" } else { "tank" }
+        ));
+        text.push_str(&format!("mouse_sensitivity = {}\n", self.mouse_sensitivity));
+        text.push_str(&format!("invert_mouse = {}\n", self.invert_mouse));
+
+        for action in Action::ALL {
+            // Las teclas sin nombre no se podrían volver a leer: se omiten
+            let names: Vec<&str> = self.bindings(action).iter().filter_map(|&binding| binding_name(binding)).collect();
+            text.push_str(&format!("{} = {}\n", action.config_name(), names.join(", ")));
+        }
+
+        fs::write(path, text)
+    }
+
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn is_down(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.bindings(action).iter().any(|&binding| match binding {
+            Binding::Key(key) => rl.is_key_down(key),
+            Binding::Mouse(button) => rl.is_mouse_button_down(button),
+        })
+    }
+
+    pub fn is_pressed(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.bindings(action).iter().any(|&binding| match binding {
+            Binding::Key(key) => rl.is_key_pressed(key),
+            Binding::Mouse(button) => rl.is_mouse_button_pressed(button),
+        })
+    }
+
+    // Giro pedido por el eje horizontal del mouse en este cuadro, en radianes
+    pub fn look_axis(&self, rl: &RaylibHandle) -> f32 {
+        let turn = rl.get_mouse_delta().x * self.mouse_sensitivity;
+        if self.invert_mouse { -turn } else { turn }
+    }
+
+    // Texto de la primera tecla asignada, para mostrar en pantalla
+    pub fn label(&self, action: Action) -> String {
+        match self.bindings(action).first() {
+            Some(&binding) => format!("[{}]", binding_name(binding).unwrap_or("?")),
+            None => "[sin asignar]".to_string(),
+        }
+    }
+}
+
+fn parse_binding(name: &str) -> Option<Binding> {
+    let name = name.to_uppercase();
+    MOUSE_NAMES
+        .iter()
+        .find(|(candidate, _)| *candidate == name)
+        .map(|&(_, button)| Binding::Mouse(button))
+        .or_else(|| {
+            KEY_NAMES
+                .iter()
+                .find(|(candidate, _)| *candidate == name)
+                .map(|&(_, key)| Binding::Key(key))
+        })
+}
+
+fn binding_name(binding: Binding) -> Option<&'static str> {
+    match binding {
+        Binding::Key(key) => KEY_NAMES.iter().find(|(_, k)| *k == key).map(|&(name, _)| name),
+        Binding::Mouse(button) => MOUSE_NAMES.iter().find(|(_, b)| *b == button).map(|&(name, _)| name),
+    }
+}
+
+const MOUSE_NAMES: [(&str, MouseButton); 3] = [
+    ("MOUSE_LEFT", MouseButton::MOUSE_BUTTON_LEFT),
+    ("MOUSE_RIGHT", MouseButton::MOUSE_BUTTON_RIGHT),
+    ("MOUSE_MIDDLE", MouseButton::MOUSE_BUTTON_MIDDLE),
+];
+
+const KEY_NAMES: [(&str, KeyboardKey); 105] = [
+    ("A", KeyboardKey::KEY_A),
+    ("B", KeyboardKey::KEY_B),
+    ("C", KeyboardKey::KEY_C),
+    ("D", KeyboardKey::KEY_D),
+    ("E", KeyboardKey::KEY_E),
+    ("F", KeyboardKey::KEY_F),
+    ("G", KeyboardKey::KEY_G),
+    ("H", KeyboardKey::KEY_H),
+    ("I", KeyboardKey::KEY_I),
+    ("J", KeyboardKey::KEY_J),
+    ("K", KeyboardKey::KEY_K),
+    ("L", KeyboardKey::KEY_L),
+    ("M", KeyboardKey::KEY_M),
+    ("N", KeyboardKey::KEY_N),
+    ("O", KeyboardKey::KEY_O),
+    ("P", KeyboardKey::KEY_P),
+    ("Q", KeyboardKey::KEY_Q),
+    ("R", KeyboardKey::KEY_R),
+    ("S", KeyboardKey::KEY_S),
+    ("T", KeyboardKey::KEY_T),
+    ("U", KeyboardKey::KEY_U),
+    ("V", KeyboardKey::KEY_V),
+    ("W", KeyboardKey::KEY_W),
+    ("X", KeyboardKey::KEY_X),
+    ("Y", KeyboardKey::KEY_Y),
+    ("Z", KeyboardKey::KEY_Z),
+    ("0", KeyboardKey::KEY_ZERO),
+    ("1", KeyboardKey::KEY_ONE),
+    ("2", KeyboardKey::KEY_TWO),
+    ("3", KeyboardKey::KEY_THREE),
+    ("4", KeyboardKey::KEY_FOUR),
+    ("5", KeyboardKey::KEY_FIVE),
+    ("6", KeyboardKey::KEY_SIX),
+    ("7", KeyboardKey::KEY_SEVEN),
+    ("8", KeyboardKey::KEY_EIGHT),
+    ("9", KeyboardKey::KEY_NINE),
+    ("UP", KeyboardKey::KEY_UP),
+    ("DOWN", KeyboardKey::KEY_DOWN),
+    ("LEFT", KeyboardKey::KEY_LEFT),
+    ("RIGHT", KeyboardKey::KEY_RIGHT),
+    ("SPACE", KeyboardKey::KEY_SPACE),
+    ("ENTER", KeyboardKey::KEY_ENTER),
+    ("ESCAPE", KeyboardKey::KEY_ESCAPE),
+    ("TAB", KeyboardKey::KEY_TAB),
+    ("BACKSPACE", KeyboardKey::KEY_BACKSPACE),
+    ("INSERT", KeyboardKey::KEY_INSERT),
+    ("DELETE", KeyboardKey::KEY_DELETE),
+    ("HOME", KeyboardKey::KEY_HOME),
+    ("END", KeyboardKey::KEY_END),
+    ("PAGE_UP", KeyboardKey::KEY_PAGE_UP),
+    ("PAGE_DOWN", KeyboardKey::KEY_PAGE_DOWN),
+    ("LEFT_SHIFT", KeyboardKey::KEY_LEFT_SHIFT),
+    ("RIGHT_SHIFT", KeyboardKey::KEY_RIGHT_SHIFT),
+    ("LEFT_CONTROL", KeyboardKey::KEY_LEFT_CONTROL),
+    ("RIGHT_CONTROL", KeyboardKey::KEY_RIGHT_CONTROL),
+    ("LEFT_ALT", KeyboardKey::KEY_LEFT_ALT),
+    ("RIGHT_ALT", KeyboardKey::KEY_RIGHT_ALT),
+    ("COMMA", KeyboardKey::KEY_COMMA),
+    ("PERIOD", KeyboardKey::KEY_PERIOD),
+    ("SEMICOLON", KeyboardKey::KEY_SEMICOLON),
+    ("APOSTROPHE", KeyboardKey::KEY_APOSTROPHE),
+    ("SLASH", KeyboardKey::KEY_SLASH),
+    ("BACKSLASH", KeyboardKey::KEY_BACKSLASH),
+    ("MINUS", KeyboardKey::KEY_MINUS),
+    ("EQUAL", KeyboardKey::KEY_EQUAL),
+    ("LEFT_BRACKET", KeyboardKey::KEY_LEFT_BRACKET),
+    ("RIGHT_BRACKET", KeyboardKey::KEY_RIGHT_BRACKET),
+    ("GRAVE", KeyboardKey::KEY_GRAVE),
+    ("F1", KeyboardKey::KEY_F1),
+    ("F2", KeyboardKey::KEY_F2),
+    ("F3", KeyboardKey::KEY_F3),
+    ("F4", KeyboardKey::KEY_F4),
+    ("F5", KeyboardKey::KEY_F5),
+    ("F6", KeyboardKey::KEY_F6),
+    ("F7", KeyboardKey::KEY_F7),
+    ("F8", KeyboardKey::KEY_F8),
+    ("F9", KeyboardKey::KEY_F9),
+    ("F10", KeyboardKey::KEY_F10),
+    ("F11", KeyboardKey::KEY_F11),
+    ("F12", KeyboardKey::KEY_F12),
+    ("KP_0", KeyboardKey::KEY_KP_0),
+    ("KP_1", KeyboardKey::KEY_KP_1),
+    ("KP_2", KeyboardKey::KEY_KP_2),
+    ("KP_3", KeyboardKey::KEY_KP_3),
+    ("KP_4", KeyboardKey::KEY_KP_4),
+    ("KP_5", KeyboardKey::KEY_KP_5),
+    ("KP_6", KeyboardKey::KEY_KP_6),
+    ("KP_7", KeyboardKey::KEY_KP_7),
+    ("KP_8", KeyboardKey::KEY_KP_8),
+    ("KP_9", KeyboardKey::KEY_KP_9),
+    ("KP_DECIMAL", KeyboardKey::KEY_KP_DECIMAL),
+    ("KP_DIVIDE", KeyboardKey::KEY_KP_DIVIDE),
+    ("KP_MULTIPLY", KeyboardKey::KEY_KP_MULTIPLY),
+    ("KP_SUBTRACT", KeyboardKey::KEY_KP_SUBTRACT),
+    ("KP_ADD", KeyboardKey::KEY_KP_ADD),
+    ("KP_ENTER", KeyboardKey::KEY_KP_ENTER),
+    ("KP_EQUAL", KeyboardKey::KEY_KP_EQUAL),
+    ("CAPS_LOCK", KeyboardKey::KEY_CAPS_LOCK),
+    ("SCROLL_LOCK", KeyboardKey::KEY_SCROLL_LOCK),
+    ("NUM_LOCK", KeyboardKey::KEY_NUM_LOCK),
+    ("PRINT_SCREEN", KeyboardKey::KEY_PRINT_SCREEN),
+    ("PAUSE", KeyboardKey::KEY_PAUSE),
+    ("LEFT_SUPER", KeyboardKey::KEY_LEFT_SUPER),
+    ("RIGHT_SUPER", KeyboardKey::KEY_RIGHT_SUPER),
+    ("KB_MENU", KeyboardKey::KEY_KB_MENU),
+];
@@ -0,0 +1,105 @@
+// key.rs
+
+use raylib::prelude::*;
+
+use crate::sprite::Sprite;
+
+// Llaves de colores, una por emoción. Abren las salidas y puertas con candado
+// del mismo color.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyColor {
+    Joy,
+    Anxiety,
+    Envy,
+}
+
+impl KeyColor {
+    pub const ALL: [KeyColor; 3] = [KeyColor::Joy, KeyColor::Anxiety, KeyColor::Envy];
+
+    // Nombre que se usa en los archivos de nivel
+    pub fn config_name(self) -> &'static str {
+        match self {
+            KeyColor::Joy => "alegria",
+            KeyColor::Anxiety => "ansiedad",
+            KeyColor::Envy => "envidia",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<KeyColor> {
+        let name = name.trim().to_lowercase().replace('í', "i");
+        KeyColor::ALL.into_iter().find(|key| key.config_name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyColor::Joy => "Alegría",
+            KeyColor::Anxiety => "Ansiedad",
+            KeyColor::Envy => "Envidia",
+        }
+    }
+
+    // Los mismos colores que las tarjetas del menú
+    pub fn color(self) -> Color {
+        match self {
+            KeyColor::Joy => Color::new(255, 215, 0, 255),
+            KeyColor::Anxiety => Color::new(255, 140, 0, 255),
+            KeyColor::Envy => Color::new(138, 43, 226, 255),
+        }
+    }
+
+    // Textura del TextureManager con la que se dibuja la llave en el mundo
+    pub fn texture(self) -> char {
+        match self {
+            KeyColor::Joy => '1',
+            KeyColor::Anxiety => '2',
+            KeyColor::Envy => '3',
+        }
+    }
+}
+
+// Llaves que juntó el jugador en el nivel actual
+#[derive(Clone, Default, Debug)]
+pub struct Inventory {
+    keys: Vec<KeyColor>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, key: KeyColor) {
+        if !self.has(key) {
+            self.keys.push(key);
+        }
+    }
+
+    pub fn has(&self, key: KeyColor) -> bool {
+        self.keys.contains(&key)
+    }
+
+    pub fn keys(&self) -> &[KeyColor] {
+        &self.keys
+    }
+}
+
+// Sprites de las llaves que todavía no se juntaron, flotando sobre el piso
+pub fn key_sprites(keys: &[(KeyColor, (usize, usize))], block_size: usize) -> Vec<Sprite> {
+    let size = block_size as f32;
+    keys.iter()
+        .map(|&(key, (i, j))| Sprite {
+            pos: Vector2::new((i as f32 + 0.5) * size, (j as f32 + 0.5) * size),
+            texture: key.texture(),
+            scale: 0.3,
+            offset: 0.25,
+        })
+        .collect()
+}
+
+// Imagen de una llave de perfil sobre fondo transparente
+pub fn key_image(key: KeyColor) -> Image {
+    let color = key.color();
+    let mut image = Image::gen_image_color(64, 64, Color::BLANK);
+    image.draw_circle(32, 16, 13, color); // anillo
+    image.draw_circle(32, 16, 6, Color::BLANK);
+    image.draw_rectangle(29, 28, 7, 34, color); // caña
+    image.draw_rectangle(36, 46, 10, 5, color); // dientes
+    image.draw_rectangle(36, 55, 7, 5, color);
+    image
+}
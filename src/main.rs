@@ -5,28 +5,115 @@ mod caster;
 mod player;
 mod textures;
 mod menu;
+mod camera;
+mod validate;
+mod generator;
+mod solver;
+mod input;
+mod collision;
+mod headless;
+mod sprite;
+mod door;
+mod key;
+mod enemy;
+mod fog;
+mod save;
 
 use line::line;
-use maze::{Maze,load_maze};
-use caster::{cast_ray, Intersect};
-use framebuffer::Framebuffer;
-use player::{Player, process_events};
-use textures::TextureManager;
+use maze::{Algorithm, GenConfig, Level, Maze, Surfaces, load_maze, maze_width};
+use caster::{cast_ray, map_scale, march, visibility_polygon, Face, RayConfig, Void};
+use framebuffer::{Column, Framebuffer};
+use player::{Player, process_events, process_mouse};
+use input::{Action, InputMap};
+use textures::{distance_shade, shade, TextureManager};
 use menu::{MenuImages};
+use solver::Hint;
+use camera::Camera;
+use door::{DoorUse, Doors, SWITCH};
+use key::{Inventory, KeyColor};
+use enemy::{Enemy, spawn_enemies};
+use fog::Explored;
+use save::{SAVE_FILE, SaveData};
 
 use raylib::prelude::Texture2D;
 use raylib::prelude::*;
 use raylib::color::Color;
+use std::fs;
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use std::f32::consts::PI;
 
+const LEVEL_FILES: [&str; 3] = ["maze_childhood.txt", "maze_teen.txt", "maze_adulthood.txt"];
+const DEFAULT_MUSIC: &str = "assets/background.mp3";
+const TICK: f32 = 1.0 / 120.0;     // duración de un paso de simulación (segundos)
+const MAX_FRAME_TIME: f32 = 0.25;  // tope para no acumular pasos tras una pausa larga
+const UI_REFERENCE_HEIGHT: f32 = 900.0; // alto de pantalla para el que se diseñó el HUD
+const LOCKED_FLASH: f32 = 1.5;     // segundos que parpadea la llave que pide un candado
+
+enum LevelChoice {
+  File(&'static str),
+  Endless,  // maze generado con una semilla nueva en cada partida
+  Continue, // la partida guardada en SAVE_FILE
+}
+
 enum GameState {
   Menu,
   Playing,
+  Paused,
   Victory,
 }
 
+// Escalas de render posibles: la imagen se dibuja más chica y se estira a la ventana
+const RENDER_SCALES: [f32; 3] = [0.5, 0.75, 1.0];
+
+// Escala de render inicial: `--scale 50|75|100` (por ciento). Devuelve el índice en RENDER_SCALES
+fn render_scale(args: &[String]) -> Result<usize, String> {
+  match args.iter().position(|arg| arg == "--scale") {
+    Some(index) => args
+      .get(index + 1)
+      .and_then(|value| RENDER_SCALES.iter().position(|&scale| value == &format!("{}", (scale * 100.0) as u32)))
+      .ok_or_else(|| "--scale espera 50, 75 o 100".to_string()),
+    None => Ok(RENDER_SCALES.len() - 1),
+  }
+}
+
+// Resolución interna del framebuffer para el tamaño actual de la ventana
+fn scaled_size(window: &RaylibHandle, scale: f32) -> (u32, u32) {
+  (
+    ((window.get_screen_width() as f32 * scale) as u32).max(1),
+    ((window.get_screen_height() as f32 * scale) as u32).max(1),
+  )
+}
+
+// Hilos para el renderizado 3D: `--threads N` o, por defecto, uno por núcleo
+fn render_threads(args: &[String]) -> Result<usize, String> {
+  match args.iter().position(|arg| arg == "--threads") {
+    Some(index) => match args.get(index + 1).map(|value| value.parse::<usize>()) {
+      Some(Ok(threads)) if threads > 0 => Ok(threads),
+      _ => Err("--threads espera un entero mayor que cero".to_string()),
+    },
+    None => Ok(thread::available_parallelism().map_or(1, |n| n.get())),
+  }
+}
+
+// Nivel del modo infinito: algoritmo y semilla cambian en cada partida
+fn endless_level() -> Level {
+  let seed = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|elapsed| elapsed.as_nanos() as u64)
+    .unwrap_or(0);
+  let config = GenConfig {
+    algorithm: Algorithm::ALL[(seed % Algorithm::ALL.len() as u64) as usize],
+    seed,
+    width: 6,  // 13 x 9 celdas, igual que los niveles hechos a mano
+    height: 4,
+    braid: 0.2,
+  };
+
+  println!("Generando maze ({}, semilla {})", config.algorithm.name(), seed);
+  Level::from_maze(&format!("Infinito #{}", seed % 10000), maze::generate(&config))
+}
+
 fn cell_to_color(cell: char) -> Color {
   match cell {
     'A' => {
@@ -56,6 +143,12 @@ fn cell_to_color(cell: char) -> Color {
     'g' => {
       return Color::GRAY;
     },
+    'D' => {
+      return Color::new(139, 90, 43, 255); // puerta
+    },
+    's' => {
+      return Color::new(200, 40, 40, 255); // interruptor
+    },
     _ => {
       return Color::WHITE;
     },
@@ -66,7 +159,7 @@ fn draw_cell(
   framebuffer: &mut Framebuffer,
   xo: usize,
   yo: usize,
-  block_size: usize,
+  (width, height): (usize, usize),
   cell: char,
 ) {
   if cell == ' ' {
@@ -75,8 +168,8 @@ fn draw_cell(
   let color = cell_to_color(cell);
   framebuffer.set_current_color(color);
 
-  for x in xo..xo + block_size {
-    for y in yo..yo + block_size {
+  for x in xo..xo + width {
+    for y in yo..yo + height {
       framebuffer.set_pixel(x as u32, y as u32);
     }
   }
@@ -85,17 +178,44 @@ fn draw_cell(
 pub fn render_maze(
   framebuffer: &mut Framebuffer,
   maze: &Maze,
+  doors: &Doors,
   block_size: usize,
   player: &Player,
+  enemies: &[Enemy],
+  ray_config: &RayConfig,
 ) {
+  // El mapa se escala para que el maze entero entre en el framebuffer
+  let scale = map_scale(framebuffer, maze, block_size);
+  let cell_size = block_size as f32 * scale;
+  let edge = |k: usize| (k as f32 * cell_size).round() as usize;
+
   for (row_index, row) in maze.iter().enumerate() {
     for (col_index, &cell) in row.iter().enumerate() {
-      let xo = col_index * block_size;
-      let yo = row_index * block_size;
-      draw_cell(framebuffer, xo, yo, block_size, cell);
+      let xo = edge(col_index);
+      let yo = edge(row_index);
+      draw_cell(framebuffer, xo, yo, (edge(col_index + 1) - xo, edge(row_index + 1) - yo), cell);
+    }
+  }
+
+  // Enemigos como cuadrados de su color
+  let half = (cell_size / 4.0) as usize;
+  for enemy in enemies {
+    framebuffer.set_current_color(enemy.kind.color());
+    let (cx, cy) = ((enemy.pos.x * scale) as usize, (enemy.pos.y * scale) as usize);
+    for x in cx.saturating_sub(half)..cx + half {
+      for y in cy.saturating_sub(half)..cy + half {
+        framebuffer.set_pixel(x as u32, y as u32);
+      }
     }
   }
 
+  // Contorno de todo lo que el jugador tiene a la vista
+  let visible = visibility_polygon(maze, doors, player.pos, ray_config.max_distance, block_size);
+  framebuffer.set_current_color(Color::new(255, 240, 150, 255));
+  for (index, &point) in visible.iter().enumerate() {
+    line(framebuffer, point * scale, visible[(index + 1) % visible.len()] * scale);
+  }
+
   framebuffer.set_current_color(Color::WHITESMOKE);
 
   // draw what the player sees
@@ -103,26 +223,47 @@ pub fn render_maze(
   for i in 0..num_rays {
     let current_ray = i as f32 / num_rays as f32; // current ray divided by total rays
     let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
-    cast_ray(framebuffer, &maze, &player, a, block_size, true);
+    cast_ray(framebuffer, maze, doors, player.pos, a, block_size, ray_config);
   }
 }
 
+// Escala del HUD: las medidas están pensadas para este alto de pantalla
+fn ui_scale(framebuffer: &Framebuffer) -> f32 {
+  framebuffer.height as f32 / UI_REFERENCE_HEIGHT
+}
+
+// Medida del HUD en píxeles para la resolución actual, con un mínimo para que no desaparezca
+fn scaled(framebuffer: &Framebuffer, value: f32, min: usize) -> usize {
+  ((value * ui_scale(framebuffer)).round() as usize).max(min)
+}
+
 fn render_minimap(
   framebuffer: &mut Framebuffer,
   maze: &Maze,
   player: &Player,
+  enemies: &[Enemy],
   block_size: usize,
+  hint: Option<&Hint>,
+  explored: Option<&Explored>, // None muestra el maze completo
 ) {
-  let margin = 20;
+  // El HUD se acomoda a la resolución actual
+  let margin = scaled(framebuffer, 20.0, 4);
+  let padding = scaled(framebuffer, 10.0, 3);
   
   // Calcular el tamaño real del maze
   let maze_rows = maze.len();
-  let maze_cols = if maze_rows > 0 { maze[0].len() } else { 0 };
+  let maze_cols = maze_width(maze); // las filas de un nivel abierto pueden medir distinto
   
-  // Calcular el tamaño del minimapa basado en el maze real
-  let mini_block_size = 12; // Un poco más grande para mejor visibilidad
-  let minimap_width = maze_cols * mini_block_size + 20; // +20 para padding
-  let minimap_height = maze_rows * mini_block_size + 20;
+  // Calcular el tamaño del minimapa: a lo sumo un tercio del ancho y del alto de la pantalla
+  let fit = (framebuffer.width as usize / 3 / maze_cols.max(1)).min(framebuffer.height as usize / 3 / maze_rows.max(1));
+  let mini_block_size = fit.clamp(2, 12);
+  let minimap_width = maze_cols * mini_block_size + 2 * padding;
+  let minimap_height = maze_rows * mini_block_size + 2 * padding;
+
+  // Ventana demasiado chica para el minimapa
+  if minimap_width + margin > framebuffer.width as usize || minimap_height + margin > framebuffer.height as usize {
+    return;
+  }
   
   // Posición del minimapa (esquina superior derecha)
   let minimap_x = framebuffer.width as usize - minimap_width - margin;
@@ -149,11 +290,13 @@ fn render_minimap(
       framebuffer.set_pixel((minimap_x + minimap_width - 1) as u32, y as u32);
   }
   
+  // Solo se dibuja lo que el jugador ya vio
+  let seen = |i: usize, j: usize| explored.is_none_or(|explored| explored.is_explored(i as isize, j as isize));
+
   // Dibujar el maze en el minimapa con padding
-  let padding = 10;
   for (row_index, row) in maze.iter().enumerate() {
       for (col_index, &cell) in row.iter().enumerate() {
-          if cell != ' ' {
+          if cell != ' ' && seen(col_index, row_index) {
               let color = cell_to_color(cell);
               framebuffer.set_current_color(color);
               
@@ -172,6 +315,35 @@ fn render_minimap(
       }
   }
   
+  // Dibujar el camino de la pista (puntos celestes)
+  if let Some(hint) = hint {
+      framebuffer.set_current_color(Color::SKYBLUE);
+      for &(i, j) in &hint.path {
+          let center_x = minimap_x + padding + i * mini_block_size + mini_block_size / 2;
+          let center_y = minimap_y + padding + j * mini_block_size + mini_block_size / 2;
+          for x in center_x - 2..=center_x + 1 {
+              for y in center_y - 2..=center_y + 1 {
+                  framebuffer.set_pixel(x as u32, y as u32);
+              }
+          }
+      }
+  }
+  
+  // Dibujar los enemigos (puntos de su color) que estén en zonas exploradas
+  for enemy in enemies {
+      if !seen((enemy.pos.x / block_size as f32) as usize, (enemy.pos.y / block_size as f32) as usize) {
+          continue;
+      }
+      let enemy_x = minimap_x + padding + (enemy.pos.x / block_size as f32 * mini_block_size as f32) as usize;
+      let enemy_y = minimap_y + padding + (enemy.pos.y / block_size as f32 * mini_block_size as f32) as usize;
+      framebuffer.set_current_color(enemy.kind.color());
+      for x in enemy_x.saturating_sub(2)..=enemy_x + 2 {
+          for y in enemy_y.saturating_sub(2)..=enemy_y + 2 {
+              framebuffer.set_pixel(x as u32, y as u32);
+          }
+      }
+  }
+  
   // Calcular la posición del jugador en el minimapa
   let player_grid_x = (player.pos.x / block_size as f32) as usize;
   let player_grid_y = (player.pos.y / block_size as f32) as usize;
@@ -193,7 +365,7 @@ fn render_minimap(
   
   // Dibujar dirección del jugador (línea roja)
   framebuffer.set_current_color(Color::RED);
-  let dir_length = 20.0;
+  let dir_length = (20.0 * ui_scale(framebuffer)).max(6.0);
   let end_x = player_mini_x as f32 + player.a.cos() * dir_length;
   let end_y = player_mini_y as f32 + player.a.sin() * dir_length;
   
@@ -202,95 +374,151 @@ fn render_minimap(
        Vector2::new(end_x, end_y));
 }
 
+// Estado del mundo que se dibuja en un cuadro
+struct Scene<'a> {
+  level: &'a Level,
+  doors: &'a Doors,
+  hint: Option<&'a Hint>,
+  explored: &'a Explored, // los rayos marcan las celdas que se ven
+}
+
 fn render_world(
   framebuffer: &mut Framebuffer,
-  maze: &Maze,
+  scene: &Scene,
   block_size: usize,
   player: &Player,
   texture_manager: &TextureManager, // Agregar parámetro de texturas
+  ray_config: &RayConfig,
 ) {
-  let num_rays = framebuffer.width;
-  let hh = framebuffer.height as f32 / 2.0;
+  let level = scene.level;
+  let camera = Camera::from_player(player, framebuffer.width, framebuffer.height);
+  let hh = camera.horizon();
   let texture_size = 128.0; // Tamaño estándar de textura como sugirió tu maestro
+  let floor_caster = FloorCaster {
+    camera: &camera,
+    surfaces: &level.surfaces,
+    texture_manager,
+    block_size,
+    hint: scene.hint,
+  };
 
-  for i in 0..num_rays {
-    let current_ray = i as f32 / num_rays as f32;
-    let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
-    let intersect = cast_ray(framebuffer, &maze, &player, a, block_size, false);
+  // Cada columna es independiente: un rayo y una tira vertical texturizada.
+  // La distancia a la pared de cada columna queda en el depth buffer para
+  // que lo que se dibuje después (sprites, partículas) quede tapado por las paredes.
+  framebuffer.render_columns(|i, column| {
+    let height = column.len();
+    let a = camera.ray_angle(i);
+    let intersect = march(&level.maze, scene.doors, player.pos, a, block_size, ray_config);
+    scene.explored.mark_ray(player.pos, intersect.point, block_size as f32);
 
-    let distance_to_wall = intersect.distance;
-    let distance_to_projection_plane = 70.0;
-    let stake_height = (hh / distance_to_wall) * distance_to_projection_plane;
+    // El rayo se perdió en el vacío: solo hay piso y techo en esta columna
+    if !intersect.hit {
+      floor_caster.render_column(column, i, hh as usize);
+      return f32::INFINITY;
+    }
 
-    let stake_top = (hh - (stake_height / 2.0)) as usize;
-    let stake_bottom = (hh + (stake_height / 2.0)) as usize;
+    scene.explored.mark(intersect.i as isize, intersect.j as isize);
+    let distance_to_wall = camera.perpendicular_distance(intersect.distance, a);
+    let stake_height = camera.projected_height(block_size as f32, distance_to_wall);
 
-    // Calcular punto de impacto
-    let hit_x = player.pos.x + distance_to_wall * a.cos();
-    let hit_y = player.pos.y + distance_to_wall * a.sin();
-    
-    // Calcular qué celda del maze fue golpeada
-    let map_x = (hit_x / block_size as f32).floor();
-    let map_y = (hit_y / block_size as f32).floor();
-    
-    // Calcular la posición relativa dentro de la celda (0.0 a 1.0)
-    let cell_x = hit_x / block_size as f32 - map_x;
-    let cell_y = hit_y / block_size as f32 - map_y;
-    
-    // Determinar qué cara de la pared fue golpeada
-    let hit_side = {
-        let dx = hit_x - (map_x * block_size as f32 + block_size as f32 / 2.0);
-        let dy = hit_y - (map_y * block_size as f32 + block_size as f32 / 2.0);
-        
-        if dx.abs() > dy.abs() {
-            if dx > 0.0 { "east" } else { "west" }
-        } else {
-            if dy > 0.0 { "south" } else { "north" }
-        }
-    };
-    
-    // Calcular coordenada X de la textura basada en la cara golpeada
-    let tx = match hit_side {
-        "north" | "south" => (cell_x * texture_size) as u32,
-        "east" | "west" => (cell_y * texture_size) as u32,
-        _ => 0,
-    };
+    let stake_top_f = hh - (stake_height / 2.0);
+    let stake_top = stake_top_f.max(0.0) as usize;
+    let stake_bottom = (hh + (stake_height / 2.0)).min(height as f32) as usize;
+
+    floor_caster.render_column(column, i, stake_bottom);
+
+    // Calcular coordenada X de la textura con la posición exacta sobre la pared
+    let tx = (intersect.tex_u * texture_size) as u32;
     
     // Asegurar que tx esté en rango válido
     let tx = tx.min(127);
 
+    // Aplicar sombreado basado en la distancia y cara
+    let mut shade_factor = distance_shade(distance_to_wall);
+    
+    // Diferentes tonos para diferentes caras (efecto 3D)
+    shade_factor *= match intersect.face {
+        Face::North => 1.0,    // Cara más clara
+        Face::South => 0.8,    // Cara más oscura  
+        Face::East => 0.9,     // Cara intermedia
+        Face::West => 0.7,     // Cara más oscura
+    };
+
     // Renderizar la columna con textura
     for y in stake_top..stake_bottom {
-      if y < framebuffer.height as usize {
-        // Calcular coordenada Y de la textura (0 a 127)
-        let texture_y_ratio = (y - stake_top) as f32 / (stake_bottom - stake_top).max(1) as f32;
-        let ty = (texture_y_ratio * texture_size) as u32;
-        let ty = ty.min(127);
-        
-        // Obtener el color del pixel de la textura
-        let color = texture_manager.get_pixel_color(intersect.impact, tx, ty);
-        
-        // Aplicar sombreado basado en la distancia y cara
-        let mut shade_factor = (1.0 - (distance_to_wall / 800.0).min(1.0)) * 0.7 + 0.3;
-        
-        // Diferentes tonos para diferentes caras (efecto 3D)
-        shade_factor *= match hit_side {
-            "north" => 1.0,    // Cara más clara
-            "south" => 0.8,    // Cara más oscura  
-            "east" => 0.9,     // Cara intermedia
-            "west" => 0.7,     // Cara más oscura
-            _ => 0.8,
+      // Calcular coordenada Y de la textura (0 a 127) sobre la altura completa de la pared
+      let texture_y_ratio = (y as f32 - stake_top_f) / stake_height;
+      let ty = (texture_y_ratio * texture_size) as u32;
+      let ty = ty.min(127);
+      
+      // Obtener el color del pixel de la textura
+      let color = texture_manager.get_pixel_color(intersect.impact, tx, ty);
+      column[y] = shade(color, shade_factor);
+    }
+
+    distance_to_wall
+  });
+}
+
+// Piso y techo texturizados: se arma una vez por cuadro y dibuja columna por columna
+struct FloorCaster<'a> {
+  camera: &'a Camera,
+  surfaces: &'a Surfaces,
+  texture_manager: &'a TextureManager,
+  block_size: usize,
+  hint: Option<&'a Hint>,
+}
+
+impl FloorCaster<'_> {
+  // Proyecta cada fila de la columna bajo la pared sobre el piso y la refleja en el techo
+  fn render_column(&self, pixels: &mut Column, column: u32, floor_start: usize) {
+    let camera = self.camera;
+    let hh = camera.horizon();
+    let size = self.block_size as f32;
+    let texture_size = 128.0;
+
+    let a = camera.ray_angle(column);
+    let cos_correction = (a - camera.a).cos();
+    let eye_height = size / 2.0; // las paredes miden una celda y el ojo está a la mitad
+
+    let height = pixels.len();
+    for y in floor_start..height {
+      let row = y as f32 + 0.5 - hh;
+      if row <= 0.0 {
+        continue;
+      }
+
+      // Distancia perpendicular a la que esta fila toca el piso y punto del mundo
+      let perpendicular = eye_height * camera.projection_distance / row;
+      let distance = perpendicular / cos_correction;
+      let world_x = camera.pos.x + distance * a.cos();
+      let world_y = camera.pos.y + distance * a.sin();
+
+      let cell_i = (world_x / size).floor();
+      let cell_j = (world_y / size).floor();
+      let u = world_x / size - cell_i;
+      let v = world_y / size - cell_j;
+      let tx = ((u * texture_size) as u32).min(127);
+      let ty = ((v * texture_size) as u32).min(127);
+
+      let shade_factor = distance_shade(perpendicular);
+
+      // La fila simétrica respecto al horizonte ve el techo en el mismo punto
+      let ceiling_y = height - 1 - y;
+
+      if let Some(floor) = self.surfaces.floor_at(cell_i as isize, cell_j as isize) {
+        // Migas de pan de la pista: un punto brillante en el centro de cada celda del camino
+        let on_hint = self.hint.is_some_and(|hint| hint.contains(cell_i as isize, cell_j as isize));
+        let color = if on_hint && (u - 0.5).powi(2) + (v - 0.5).powi(2) < 0.015 {
+          Color::new(255, 230, 90, 255)
+        } else {
+          self.texture_manager.get_pixel_color(floor, tx, ty)
         };
-        
-        let shaded_color = Color::new(
-          (color.r as f32 * shade_factor) as u8,
-          (color.g as f32 * shade_factor) as u8,
-          (color.b as f32 * shade_factor) as u8,
-          color.a,
-        );
-        
-        framebuffer.set_current_color(shaded_color);
-        framebuffer.set_pixel(i, y as u32);
+        pixels[y] = shade(color, shade_factor);
+      }
+      if let Some(ceiling) = self.surfaces.ceiling_at(cell_i as isize, cell_j as isize) {
+        let color = self.texture_manager.get_pixel_color(ceiling, tx, ty);
+        pixels[ceiling_y] = shade(color, shade_factor * 0.8);
       }
     }
   }
@@ -298,20 +526,26 @@ fn render_world(
 
 fn render_fps(framebuffer: &mut Framebuffer, fps: f32) {
   // Renderizar barra y número de FPS en la esquina superior izquierda
-  let bar_width = (fps / 60.0 * 100.0).min(100.0) as usize;
+  let ui_scale = ui_scale(framebuffer);
+
   let fps_text = format!("{:.0}", fps);
+  let margin = scaled(framebuffer, 10.0, 1);
+  let padding = scaled(framebuffer, 5.0, 1);
+  let bar_max = scaled(framebuffer, 100.0, 1);
+  let bar_width = ((fps / 60.0).min(1.0) * bar_max as f32) as usize;
+  let bar_height = scaled(framebuffer, 5.0, 1);
   
   // Calcular dimensiones totales
-  let char_width = 8;
-  let char_height = 9;
+  let char_width = scaled(framebuffer, 8.0, 1);
+  let char_height = scaled(framebuffer, 9.0, 1);
   let text_width = fps_text.len() * char_width;
-  let total_width = 110.max(text_width + 8); // Asegurar que sea al menos tan ancho como la barra
-  let total_height = 35; // Altura para barra + texto + espaciado
+  let total_width = scaled(framebuffer, 110.0, 1).max(text_width + scaled(framebuffer, 8.0, 1)); // Asegurar que sea al menos tan ancho como la barra
+  let total_height = (bar_height + char_height + 3 * padding).max(scaled(framebuffer, 35.0, 1)); // Altura para barra + texto + espaciado
   
   // Fondo para todo el indicador de FPS
   framebuffer.set_current_color(Color::new(0, 0, 0, 200));
-  for x in 10..10 + total_width {
-      for y in 10..10 + total_height {
+  for x in margin..margin + total_width {
+      for y in margin..margin + total_height {
           framebuffer.set_pixel(x as u32, y as u32);
       }
   }
@@ -327,37 +561,85 @@ fn render_fps(framebuffer: &mut Framebuffer, fps: f32) {
   
   // Dibujar la barra de FPS
   framebuffer.set_current_color(bar_color);
-  for x in 15..15 + bar_width {
-      for y in 15..20 {
+  let bar_x = margin + padding;
+  let bar_y = margin + padding;
+  for x in bar_x..bar_x + bar_width {
+      for y in bar_y..bar_y + bar_height {
           framebuffer.set_pixel(x as u32, y as u32);
       }
   }
   
   // Dibujar el número de FPS debajo de la barra
   framebuffer.set_current_color(bar_color); // Usar el mismo color que la barra
-  let text_start_x = 15; // Alineado con el inicio de la barra
-  let text_start_y = 25; // Debajo de la barra
+  let text_start_x = bar_x; // Alineado con el inicio de la barra
+  let text_start_y = bar_y + bar_height + padding; // Debajo de la barra
   
   for (i, ch) in fps_text.chars().enumerate() {
     let char_x = text_start_x + i * char_width;
-    draw_digit(framebuffer, char_x, text_start_y, ch);
+    draw_digit(framebuffer, char_x, text_start_y, ch, ui_scale);
   }
   
   // Marco blanco alrededor de todo
   framebuffer.set_current_color(Color::WHITE);
   // Borde superior e inferior
-  for x in 10..10 + total_width {
-      framebuffer.set_pixel(x as u32, 10);
-      framebuffer.set_pixel(x as u32, (10 + total_height - 1) as u32);
+  for x in margin..margin + total_width {
+      framebuffer.set_pixel(x as u32, margin as u32);
+      framebuffer.set_pixel(x as u32, (margin + total_height - 1) as u32);
   }
   // Borde izquierdo y derecho
-  for y in 10..10 + total_height {
-      framebuffer.set_pixel(10, y as u32);
-      framebuffer.set_pixel((10 + total_width - 1) as u32, y as u32);
+  for y in margin..margin + total_height {
+      framebuffer.set_pixel(margin as u32, y as u32);
+      framebuffer.set_pixel((margin + total_width - 1) as u32, y as u32);
   }
 }
 
-fn draw_digit(framebuffer: &mut Framebuffer, x: usize, y: usize, digit: char) {
+// Llaves del nivel en la esquina inferior izquierda: llenas las que ya se
+// juntaron y solo con borde las que faltan. `flash` resalta la que pidió un candado.
+fn render_keys(framebuffer: &mut Framebuffer, level: &Level, inventory: &Inventory, flash: Option<KeyColor>) {
+  let count = level.keys.len();
+  if count == 0 {
+    return;
+  }
+
+  let margin = scaled(framebuffer, 20.0, 4);
+  let slot = scaled(framebuffer, 28.0, 8);
+  let gap = slot / 3;
+  let border = (slot / 10).max(1);
+
+  // Ventana demasiado chica para el indicador
+  let total_width = count * slot + (count - 1) * gap;
+  if total_width + 2 * margin > framebuffer.width as usize || slot + margin > framebuffer.height as usize {
+    return;
+  }
+
+  let top = framebuffer.height as usize - margin - slot;
+  for (index, &(key, _)) in level.keys.iter().enumerate() {
+      let left = margin + index * (slot + gap);
+
+      // Fondo: el color de la llave si ya está en el inventario
+      let fill = if inventory.has(key) { key.color() } else { Color::new(0, 0, 0, 200) };
+      framebuffer.set_current_color(fill);
+      for x in left..left + slot {
+          for y in top..top + slot {
+              framebuffer.set_pixel(x as u32, y as u32);
+          }
+      }
+
+      // Borde del color de la llave, o blanco si es la que falta para el candado
+      let edge = if flash == Some(key) { Color::WHITE } else { key.color() };
+      framebuffer.set_current_color(edge);
+      for x in left..left + slot {
+          for y in top..top + slot {
+              let inside = x >= left + border && x < left + slot - border && y >= top + border && y < top + slot - border;
+              if !inside {
+                  framebuffer.set_pixel(x as u32, y as u32);
+              }
+          }
+      }
+  }
+}
+
+fn draw_digit(framebuffer: &mut Framebuffer, x: usize, y: usize, digit: char, scale: f32) {
   // Patrones de píxeles para cada dígito (7x9 píxeles)
   let patterns = match digit {
     '0' => [
@@ -483,14 +765,17 @@ fn draw_digit(framebuffer: &mut Framebuffer, x: usize, y: usize, digit: char) {
     ]
   };
   
-  // Dibujar el patrón
+  // Dibujar el patrón: cada punto del patrón ocupa `scale` píxeles por lado
+  let edge = |k: usize| (k as f32 * scale).round() as usize;
   for (row, pattern) in patterns.iter().enumerate() {
     for (col, ch) in pattern.chars().enumerate() {
       if ch == '#' {
-        let px = x + col;
-        let py = y + row;
-        if px < framebuffer.width as usize && py < framebuffer.height as usize {
-          framebuffer.set_pixel(px as u32, py as u32);
+        for py in y + edge(row)..(y + edge(row + 1)).max(y + edge(row) + 1) {
+          for px in x + edge(col)..(x + edge(col + 1)).max(x + edge(col) + 1) {
+            if px < framebuffer.width as usize && py < framebuffer.height as usize {
+              framebuffer.set_pixel(px as u32, py as u32);
+            }
+          }
         }
       }
     }
@@ -498,6 +783,25 @@ fn draw_digit(framebuffer: &mut Framebuffer, x: usize, y: usize, digit: char) {
 }
 
 fn main() {
+  // Modo de validación para diseñadores de niveles: no abre ventana
+  let args: Vec<String> = std::env::args().collect();
+  if args.get(1).map(String::as_str) == Some("--validate") {
+    std::process::exit(validate::run(&args[2..]));
+  }
+
+  // Renderizado sin ventana ni audio: escribe los cuadros a disco
+  if args.get(1).map(String::as_str) == Some("--headless") {
+    std::process::exit(headless::run(&args[2..]));
+  }
+
+  let (render_threads, mut scale_index) = match (render_threads(&args), render_scale(&args)) {
+    (Ok(threads), Ok(scale)) => (threads, scale),
+    (Err(e), _) | (_, Err(e)) => {
+      eprintln!("{}", e);
+      std::process::exit(2);
+    }
+  };
+
   let window_width = 1300;
   let window_height = 900;
   let block_size = 100;
@@ -507,7 +811,12 @@ fn main() {
     .size(window_width, window_height)
     .title("Inside Out Maze - Raycaster")
     .log_level(TraceLogLevel::LOG_WARNING)
+    .resizable()
     .build();
+  window.set_window_min_size(320, 240);
+  // ESC es una tecla más: por defecto sale desde el menú (acción `quit`),
+  // pero se puede asignar a cualquier otra acción sin cerrar el juego
+  window.set_exit_key(None);
 
   let menu_images = match MenuImages::load(&mut window, &raylib_thread) {
     Ok(images) => {
@@ -520,7 +829,6 @@ fn main() {
     }
   };
 
-  let texture_manager = TextureManager::new(&mut window, &raylib_thread);
 
   // Inicializar el sistema de audio
   let audio = match RaylibAudio::init_audio_device() {
@@ -540,8 +848,9 @@ fn main() {
   
   // Intentar cargar música de fondo
   let mut music_opt: Option<Music> = None;
+  let mut current_track = DEFAULT_MUSIC.to_string();
   
-  match audio.new_music("assets/background.mp3") {
+  match audio.new_music(DEFAULT_MUSIC) {
       Ok(music) => {
           println!("✓ Música cargada correctamente");
           music.set_volume(0.5);
@@ -550,20 +859,49 @@ fn main() {
           println!("✓ Música iniciada");
       },
       Err(_) => {
-          println!("⚠ No se pudo cargar 'assets/background.mp3'");
+          println!("⚠ No se pudo cargar '{}'", DEFAULT_MUSIC);
           println!("  Continuando sin música...");
       }
   }
 
-  let mut framebuffer = Framebuffer::new(window_width as u32, window_height as u32);
+  let (render_width, render_height) = scaled_size(&window, RENDER_SCALES[scale_index]);
+  let mut framebuffer = Framebuffer::new(render_width, render_height);
   framebuffer.set_background_color(Color::new(153, 102, 204, 255));
+  framebuffer.set_threads(render_threads);
 
-  let mut maze = load_maze("maze_childhood.txt");
+  // Lo que ven los rayos al salir del maze lo decide cada nivel
+  let mut ray_config = RayConfig {
+    max_distance: 50.0 * block_size as f32,
+    void: Void::Open,
+  };
+
+  // Nivel vacío hasta que se elija uno en el menú
+  let mut level = Level::from_maze("", Vec::new());
+  let mut load_error: Option<String> = None;
+  let mut texture_manager = TextureManager::new(&mut window, &raylib_thread, &level.palette);
   let mut player = Player {
-    pos: Vector2::new(150.0, 150.0),
-    a: PI / 3.0,
+    pos: level.spawn_position(block_size),
+    a: level.spawn_angle,
     fov: PI / 3.0,
+    radius: level.player_radius * block_size as f32,
+    inventory: Inventory::default(),
   };
+  let mut level_ticks: u32 = 0;
+  let mut level_time = 0.0;
+  let mut show_hint = false;
+  let mut sprites = Vec::new();
+  let mut doors = Doors::new(&level);
+  let mut keys_left = Vec::new();
+  let mut locked_flash: Option<(KeyColor, f32)> = None;
+  let mut touched_lock: Option<KeyColor> = None;
+  let mut enemies: Vec<Enemy> = Vec::new();
+  let mut explored = Explored::new(&level.maze);
+  let mut reveal_map = false;
+  let mut level_file: Option<String> = None; // archivo del nivel actual; None en el modo infinito
+  let mut input = InputMap::load(input::CONFIG_FILE);
+  let mut steps = 0;
+  let mut par_steps = None;
+  let mut last_cell = (0, 0);
 
   let mut fps = 60.0;
   let mut frame_count = 0;
@@ -572,7 +910,18 @@ fn main() {
 
   let mut game_state = GameState::Menu;
 
-  while !window.window_should_close() {
+  // Reloj del juego: la ventana limita los FPS y la simulación avanza en pasos fijos
+  window.set_target_fps(60);
+  let mut clock = Instant::now();
+  let mut accumulator = 0.0;
+
+  let mut quit = false;
+
+  while !quit && !window.window_should_close() {
+    let now = Instant::now();
+    let frame_time = (now - clock).as_secs_f32().min(MAX_FRAME_TIME);
+    clock = now;
+
     if let Some(ref music) = music_opt {
         music.update_stream();
     }
@@ -587,66 +936,311 @@ fn main() {
     match game_state {
       GameState::Menu => {
           // --- Pantalla de menú ---
-          let mut d = window.begin_drawing(&raylib_thread);
-          menu::render_menu(&mut d, menu_images.as_ref());
+          let mut selected = None;
+          {
+              let mut d = window.begin_drawing(&raylib_thread);
+              menu::render_menu(&mut d, menu_images.as_ref(), load_error.as_deref(), &input);
 
-          // Selección de laberinto
-          if d.is_key_pressed(KeyboardKey::KEY_ONE) {
-              maze = load_maze("maze_childhood.txt");
-              player.pos = Vector2::new(150.0, 150.0);
-              game_state = GameState::Playing;
-          }
-          if d.is_key_pressed(KeyboardKey::KEY_TWO) {
-              maze = load_maze("maze_teen.txt");
-              player.pos = Vector2::new(150.0, 150.0);
-              game_state = GameState::Playing;
+              // El esquema elegido queda guardado para la próxima partida
+              if input.is_pressed(&d, Action::ToggleControls) {
+                  input.controls = input.controls.toggle();
+                  if let Err(e) = input.save(input::CONFIG_FILE) {
+                      println!("⚠ No se pudo guardar '{}': {}", input::CONFIG_FILE, e);
+                  }
+              }
+
+              // Selección de laberinto
+              let level_actions = [Action::Level1, Action::Level2, Action::Level3];
+              for (action, filename) in level_actions.iter().zip(LEVEL_FILES) {
+                  if input.is_pressed(&d, *action) {
+                      selected = Some(LevelChoice::File(filename));
+                  }
+              }
+              if input.is_pressed(&d, Action::Endless) {
+                  selected = Some(LevelChoice::Endless);
+              }
+              if input.is_pressed(&d, Action::Continue) {
+                  selected = Some(LevelChoice::Continue);
+              }
+              if input.is_pressed(&d, Action::Quit) {
+                  quit = true;
+              }
           }
-          if d.is_key_pressed(KeyboardKey::KEY_THREE) {
-              maze = load_maze("maze_adulthood.txt");
-              player.pos = Vector2::new(150.0, 150.0);
+
+          if let Some(choice) = selected {
+              // Al continuar se carga el nivel de la partida guardada y, más abajo,
+              // se restaura lo que tenía el jugador
+              let mut saved = None;
+              let loaded = match choice {
+                  LevelChoice::File(filename) => load_maze(filename)
+                      .map(|level| (level, Some(filename.to_string())))
+                      .map_err(|e| format!("{}: {}", filename, e)),
+                  LevelChoice::Endless => Ok((endless_level(), None)),
+                  LevelChoice::Continue => SaveData::load(SAVE_FILE).and_then(|save| {
+                      let level = load_maze(&save.level_file).map_err(|e| format!("{}: {}", save.level_file, e))?;
+                      let file = save.level_file.clone();
+                      saved = Some(save);
+                      Ok((level, Some(file)))
+                  }),
+              };
+              (level, level_file) = match loaded {
+                  Ok(loaded) => loaded,
+                  Err(e) => {
+                      println!("❌ Error cargando {}", e);
+                      load_error = Some(e);
+                      continue;
+                  }
+              };
+              load_error = None;
+              texture_manager = TextureManager::new(&mut window, &raylib_thread, &level.palette);
+              player.pos = level.spawn_position(block_size);
+              player.a = level.spawn_angle;
+
+              // Cambiar de canción solo si el nivel pide otra distinta
+              let track = level.music.clone().unwrap_or_else(|| DEFAULT_MUSIC.to_string());
+              if track != current_track {
+                  match audio.new_music(&track) {
+                      Ok(music) => {
+                          music.set_volume(0.5);
+                          music.play_stream();
+                          music_opt = Some(music);
+                      },
+                      Err(_) => println!("⚠ No se pudo cargar '{}'", track),
+                  }
+                  current_track = track;
+              }
+
+              println!("Nivel: {}", level.name);
+              level_ticks = 0;
+              accumulator = 0.0;
+
+              // Juntar las llaves y llegar a la salida por el camino más corto es el par de pasos
+              par_steps = solver::par_steps(&level);
+              steps = 0;
+              last_cell = player.grid_cell(block_size as f32);
+              show_hint = false;
+              player.inventory = Inventory::default();
+              keys_left = solver::key_order(&level);
+              locked_flash = None;
+              touched_lock = None;
+              sprites = level.world_sprites(block_size);
+              sprites.extend(key::key_sprites(&keys_left, block_size));
+              player.radius = level.player_radius * block_size as f32;
+              doors = Doors::new(&level);
+              ray_config.void = level.void;
+              enemies = spawn_enemies(&level, block_size);
+              explored = Explored::new(&level.maze);
+
+              if let Some(save) = saved {
+                  player.pos = save.pos * block_size as f32;
+                  player.a = save.angle;
+                  for &key in &save.keys {
+                      player.inventory.add(key);
+                      doors.unlock(key);
+                  }
+                  keys_left.retain(|&(key, _)| !player.inventory.has(key));
+                  sprites = level.world_sprites(block_size);
+                  sprites.extend(key::key_sprites(&keys_left, block_size));
+                  level_ticks = save.ticks;
+                  steps = save.steps;
+                  last_cell = player.grid_cell(block_size as f32);
+                  explored = Explored::from_rows(&level.maze, &save.explored);
+                  println!("Partida continuada");
+              }
               game_state = GameState::Playing;
           }
       }
 
       GameState::Playing => {
           // --- Juego principal ---
-          process_events(&mut player, &window, &maze, block_size as f32);
+          process_mouse(&mut player, &window, &input);
 
-          if window.is_key_pressed(KeyboardKey::KEY_M) {
-              mode = if mode == "2D" { "3D" } else { "2D" };
-              println!("Modo: {}", mode);
+          if input.is_pressed(&window, Action::Use) {
+              if let DoorUse::Locked(key) = doors.use_door(player.pos, player.a, block_size as f32, &player.inventory) {
+                  println!("🔒 Hace falta la llave de {}", key.name());
+                  locked_flash = Some((key, LOCKED_FLASH));
+              }
           }
 
-          if player.has_reached_goal(&maze, block_size as f32) {
+          // Simulación a paso fijo: el mismo resultado sin importar los FPS
+          accumulator += frame_time;
+          let mut reached_goal = false;
+          while accumulator >= TICK {
+              doors.update(TICK);
+              process_events(&mut player, &window, &level.maze, &doors, block_size as f32, TICK, &input);
+              accumulator -= TICK;
+              level_ticks += 1;
+
+              // Si un enemigo lo alcanza, el jugador vuelve a la aparición
+              for enemy in enemies.iter_mut() {
+                  enemy.update(&level.maze, &doors, player.pos, block_size as f32, TICK);
+              }
+              if enemies.iter().any(|enemy| enemy.touches(player.pos, player.radius, block_size as f32)) {
+                  println!("👻 ¡Te atraparon! De vuelta al inicio");
+                  player.pos = level.spawn_position(block_size);
+                  player.a = level.spawn_angle;
+                  enemies = spawn_enemies(&level, block_size);
+              }
+
+              let cell = player.grid_cell(block_size as f32);
+              if cell != last_cell {
+                  steps += 1;
+                  last_cell = cell;
+
+                  // Pisar un interruptor abre sus puertas
+                  if maze::cell_at(&level.maze, cell.0, cell.1) == Some(SWITCH) {
+                      doors.press_switch((cell.0 as usize, cell.1 as usize));
+                  }
+
+                  if cell.0 >= 0 && cell.1 >= 0 {
+                      let cell = (cell.0 as usize, cell.1 as usize);
+
+                      // Juntar la llave de la celda
+                      if let Some(index) = keys_left.iter().position(|&(_, key_cell)| key_cell == cell) {
+                          let (key, _) = keys_left.remove(index);
+                          player.inventory.add(key);
+                          doors.unlock(key);
+                          println!("🔑 Llave de {}", key.name());
+                          sprites = level.world_sprites(block_size);
+                          sprites.extend(key::key_sprites(&keys_left, block_size));
+                      }
+                  }
+              }
+
+              // Empujar una salida con candado sin su llave
+              let touching = doors.locked_goal_touching(player.pos, player.radius, block_size as f32);
+              if let Some(key) = touching {
+                  if touching != touched_lock {
+                      println!("🔒 La salida necesita la llave de {}", key.name());
+                  }
+                  locked_flash = Some((key, LOCKED_FLASH));
+              }
+              touched_lock = touching;
+
+              if player.has_reached_goal(&level, block_size as f32) {
+                  reached_goal = true;
+                  break;
+              }
+          }
+
+          if reached_goal {
+            // El nivel terminado ya no tiene nada que continuar
+            if SaveData::load(SAVE_FILE).is_ok_and(|save| Some(save.level_file) == level_file) {
+                let _ = fs::remove_file(SAVE_FILE);
+            }
+            level_time = level_ticks as f32 * TICK;
             game_state = GameState::Victory;
             continue;
           }
 
+          // Al pausar se guarda la partida; los niveles infinitos no se pueden volver a generar
+          if input.is_pressed(&window, Action::Pause) {
+              if let Some(file) = &level_file {
+                  let save = SaveData {
+                      level_file: file.clone(),
+                      pos: player.pos * (1.0 / block_size as f32),
+                      angle: player.a,
+                      ticks: level_ticks,
+                      steps,
+                      keys: player.inventory.keys().to_vec(),
+                      explored: explored.to_rows(),
+                  };
+                  match save.save(SAVE_FILE) {
+                      Ok(()) => println!("💾 Partida guardada en '{}'", SAVE_FILE),
+                      Err(e) => println!("⚠ No se pudo guardar '{}': {}", SAVE_FILE, e),
+                  }
+              }
+              game_state = GameState::Paused;
+              continue;
+          }
+
+          if input.is_pressed(&window, Action::ToggleMap) {
+              mode = if mode == "2D" { "3D" } else { "2D" };
+              println!("Modo: {}", mode);
+          }
+
+          if input.is_pressed(&window, Action::RevealMap) {
+              reveal_map = !reveal_map;
+          }
+
+          if input.is_pressed(&window, Action::ToggleHint) {
+              show_hint = !show_hint;
+          }
+
+          if input.is_pressed(&window, Action::RenderScale) {
+              scale_index = (scale_index + 1) % RENDER_SCALES.len();
+              println!("Escala de render: {:.0}%", RENDER_SCALES[scale_index] * 100.0);
+          }
+
+          // Si cambió el tamaño de la ventana o la escala, se rehace el framebuffer
+          let (render_width, render_height) = scaled_size(&window, RENDER_SCALES[scale_index]);
+          if (framebuffer.width, framebuffer.height) != (render_width, render_height) {
+              framebuffer.resize(render_width, render_height);
+          }
+
+          // La pista se recalcula desde la celda actual del jugador;
+          // mientras queden llaves, lleva a la próxima en el orden del par
+          let cell = player.grid_cell(block_size as f32);
+          let hint = if show_hint && cell.0 >= 0 && cell.1 >= 0 {
+              let start = (cell.0 as usize, cell.1 as usize);
+              match keys_left.first() {
+                  Some(&(_, key_cell)) => Hint::to_cell(&level, &player.inventory, start, key_cell),
+                  None => Hint::new(&level, &player.inventory, start),
+              }
+          } else {
+              None
+          };
+
           // Limpiar framebuffer al inicio del frame
           framebuffer.clear();
 
           // Renderizar escena
           if mode == "2D" {
-              render_maze(&mut framebuffer, &maze, block_size, &player);
+              render_maze(&mut framebuffer, &level.maze, &doors, block_size, &player, &enemies, &ray_config);
           } else {
-              render_world(&mut framebuffer, &maze, block_size, &player, &texture_manager);
-              render_minimap(&mut framebuffer, &maze, &player, block_size);
+              let scene = Scene { level: &level, doors: &doors, hint: hint.as_ref(), explored: &explored };
+              render_world(&mut framebuffer, &scene, block_size, &player, &texture_manager, &ray_config);
+              let mut visible = sprites.clone();
+              visible.extend(enemies.iter().map(Enemy::sprite));
+              sprite::render_sprites(&mut framebuffer, &player, &visible, &texture_manager, block_size);
+              let fog = (!reveal_map).then_some(&explored);
+              render_minimap(&mut framebuffer, &level.maze, &player, &enemies, block_size, hint.as_ref(), fog);
           }
 
+          // La llave que pidió un candado parpadea un rato
+          locked_flash = locked_flash
+              .map(|(key, time)| (key, time - frame_time))
+              .filter(|&(_, time)| time > 0.0);
+          let flash = locked_flash
+              .filter(|&(_, time)| (time * 3.0).fract() < 0.5)
+              .map(|(key, _)| key);
+
+          render_keys(&mut framebuffer, &level, &player.inventory, flash);
           render_fps(&mut framebuffer, fps);
           framebuffer.swap_buffers(&mut window, &raylib_thread);
       }
 
+      GameState::Paused => {
+        // El reloj del nivel no avanza mientras se está en pausa
+        let mut d = window.begin_drawing(&raylib_thread);
+        menu::render_pause_screen(&mut d, &level.name, &input);
+
+        if input.is_pressed(&d, Action::Pause) {
+            accumulator = 0.0;
+            game_state = GameState::Playing;
+        } else if input.is_pressed(&d, Action::Back) {
+            game_state = GameState::Menu;
+        }
+      }
+
       GameState::Victory => {
         let mut d = window.begin_drawing(&raylib_thread);
-        menu::render_victory_screen(&mut d);
+        menu::render_victory_screen(&mut d, level_time, level.par_time, steps, par_steps, &input);
     
-        if d.is_key_pressed(KeyboardKey::KEY_ENTER) {
+        if input.is_pressed(&d, Action::Select) {
             game_state = GameState::Menu;
         }
       }
     }
-
-    thread::sleep(Duration::from_millis(16));
   }
 }
\ No newline at end of file
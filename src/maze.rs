@@ -1,14 +1,638 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use raylib::prelude::Vector2;
+use std::f32::consts::PI;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::caster::Void;
+use crate::door::{DOOR, SWITCH};
+use crate::enemy::{EnemyKind, EnemySpawn};
+use crate::key::{Inventory, KeyColor};
+use crate::sprite::Sprite;
+use crate::validate::validate;
+
+pub use crate::generator::{Algorithm, GenConfig, generate};
 
 pub type Maze = Vec<Vec<char>>;
 
-pub fn load_maze(filename: &str) -> Maze {
-    let file = File::open(filename).unwrap();
-    let reader = BufReader::new(file);
+// Rectángulo de celdas entre dos esquinas opuestas (columna, fila)
+type CellRect = ((usize, usize), (usize, usize));
+
+// Celdas de pared con textura propia (además de las que agregue la paleta del nivel)
+pub const WALL_CELLS: [char; 8] = ['A', 'R', 'V', 'M', 'B', 'T', 'P', 'N'];
+
+// Texturas de piso y techo que siempre existen (generadas si falta el archivo)
+pub const SURFACE_CELLS: [char; 3] = ['f', 'c', SWITCH];
+
+// Radio de colisión del jugador si el nivel no pide otro, en celdas
+pub const DEFAULT_PLAYER_RADIUS: f32 = 0.3;
+
+// Nivel completo: cabecera con metadatos más la cuadrícula del maze
+//
+//   name: Infancia
+//   music: assets/background.mp3
+//   spawn_angle: 60
+//   par_time: 45
+//   player_radius: 0.3
+//   floor: f
+//   ceiling: c
+//   floor: c 3, 2 > 5, 4
+//   ceiling: A 7, 1
+//   texture: A assets/wall5.png
+//   goal: 11, 7
+//   sprite: j 3, 1
+//   sprite: o 5, 3 0.4 0.3
+//   switch: 2, 5 > 7, 3
+//   key: alegria 4, 2
+//   lock: alegria 11, 7
+//   enemy: olvidador 3, 5 > 9, 5 > 9, 1
+//   open: false
+//   void: A
+//   ---
+//   ARVMBTPNARVMB
+//   R@          T
+//   ...
+//
+// La cabecera es opcional: un archivo sin `---` es solo la cuadrícula.
+// `player_radius` es el radio de colisión del jugador en celdas (0.3 si no está).
+// El jugador aparece en `@` (o en `>`, `v`, `<`, `^`, que además fijan hacia
+// dónde mira); sin marcador aparece en la celda (1, 1).
+// Con `open: true` el nivel puede tener filas cortas y huecos en el borde.
+// `void` es lo que se ve fuera de la cuadrícula: `abierto` (cielo, por
+// defecto) o una pared de la textura indicada.
+// `floor` y `ceiling` con una celda (o un rectángulo `columna, fila >
+// columna, fila`) cambian la textura solo ahí; sin celdas, en todo el nivel.
+// Cada `sprite` pone un objeto en el centro de una celda: textura, columna,
+// fila y opcionalmente escala y altura sobre el piso (en celdas).
+// Las puertas `D` se abren con la tecla de usar o al pisar un interruptor `s`;
+// `switch` enlaza un interruptor con una puerta (sin enlaces abre todas).
+// `key` deja una llave de color en una celda y `lock` le pone a una salida o
+// a una puerta un candado que solo abre la llave de ese color.
+// `enemy` pone un enemigo en la primera celda y lo hace patrullar por las
+// demás en orden, volviendo al principio.
+pub struct Level {
+    pub name: String,
+    pub maze: Maze,
+    pub surfaces: Surfaces,
+    pub spawn: (usize, usize),        // celda (columna, fila)
+    pub spawn_angle: f32,             // radianes
+    pub goals: Vec<(usize, usize)>,   // celdas 'g' (columna, fila)
+    pub music: Option<String>,
+    pub palette: Vec<(char, String)>, // texturas propias del nivel
+    pub par_time: Option<f32>,        // segundos
+    pub player_radius: f32,           // radio de colisión del jugador, en celdas
+    pub open: bool,                   // permite bordes abiertos y filas cortas
+    pub void: Void,                   // lo que ven los rayos fuera de la cuadrícula
+    pub grid_line: usize,             // línea del archivo donde empieza la cuadrícula
+    pub sprites: Vec<Sprite>,         // posiciones en celdas
+    pub switches: Vec<((usize, usize), (usize, usize))>, // interruptor -> puerta
+    pub keys: Vec<(KeyColor, (usize, usize))>,  // llaves sueltas en el nivel
+    pub locks: Vec<(KeyColor, (usize, usize))>, // salidas y puertas con candado
+    pub enemies: Vec<EnemySpawn>,
+}
+
+impl Level {
+    // Nivel sin metadatos alrededor de una cuadrícula ya construida
+    pub fn from_maze(name: &str, maze: Maze) -> Self {
+        let goals = find_cells(&maze, 'g');
+        let surfaces = Surfaces::uniform(&maze, 'f', 'c');
+
+        Level {
+            name: name.to_string(),
+            maze,
+            surfaces,
+            spawn: (1, 1),
+            spawn_angle: PI / 3.0,
+            goals,
+            music: None,
+            palette: Vec::new(),
+            par_time: None,
+            player_radius: DEFAULT_PLAYER_RADIUS,
+            open: false,
+            void: Void::Open,
+            grid_line: 1,
+            sprites: Vec::new(),
+            switches: Vec::new(),
+            keys: Vec::new(),
+            locks: Vec::new(),
+            enemies: Vec::new(),
+        }
+    }
+
+    // Centro de la celda de aparición en coordenadas del mundo
+    pub fn spawn_position(&self, block_size: usize) -> Vector2 {
+        Vector2::new(
+            (self.spawn.0 as f32 + 0.5) * block_size as f32,
+            (self.spawn.1 as f32 + 0.5) * block_size as f32,
+        )
+    }
+
+    // Sprites del nivel con la posición convertida a coordenadas del mundo
+    pub fn world_sprites(&self, block_size: usize) -> Vec<Sprite> {
+        self.sprites
+            .iter()
+            .map(|sprite| Sprite { pos: sprite.pos * block_size as f32, ..*sprite })
+            .collect()
+    }
+
+    // Llave que le falta al inventario para pasar el candado de la celda, si tiene
+    pub fn missing_key(&self, cell: (usize, usize), inventory: &Inventory) -> Option<KeyColor> {
+        self.locks
+            .iter()
+            .find(|&&(key, lock)| lock == cell && !inventory.has(key))
+            .map(|&(key, _)| key)
+    }
+
+    // Línea y columna del archivo (desde 1) de una celda de la cuadrícula
+    pub fn file_position(&self, (i, j): (usize, usize)) -> (usize, usize) {
+        (self.grid_line + j, i + 1)
+    }
+
+    // Caracteres de celda que este nivel sabe dibujar
+    pub fn is_known_cell(&self, cell: char) -> bool {
+        cell == ' '
+            || cell == 'g'
+            || cell == DOOR
+            || cell == SWITCH
+            || WALL_CELLS.contains(&cell)
+            || self.palette.iter().any(|(ch, _)| *ch == cell)
+    }
+}
+
+#[derive(Debug)]
+pub enum MazeError {
+    Io { path: String, source: io::Error },
+    InvalidHeader { line: usize, message: String },
+    RaggedRow { line: usize, expected: usize, found: usize },
+    UnknownCell { line: usize, column: usize, cell: char },
+    MissingGoal,
+    MissingWall { line: usize, column: usize },
+    SpawnBlocked { line: usize, column: usize },
+    NoPath { line: usize, column: usize },
+    UnreachableGoal { line: usize, column: usize },
+    UnreachableKey { line: usize, column: usize, key: KeyColor },
+    MultipleGoals { count: usize },
+    DeadZone { line: usize, column: usize, cells: usize },
+    HiddenSprite { line: usize, column: usize, texture: char },
+    DoorWithoutFrame { line: usize, column: usize },
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MazeError::Io { path, source } => write!(f, "no se pudo leer '{}': {}", path, source),
+            MazeError::InvalidHeader { line, message } => {
+                write!(f, "línea {}: cabecera inválida: {}", line, message)
+            }
+            MazeError::RaggedRow { line, expected, found } => {
+                write!(f, "línea {}: la fila mide {} celdas, se esperaban {}", line, found, expected)
+            }
+            MazeError::UnknownCell { line, column, cell } => {
+                write!(f, "línea {}, columna {}: celda desconocida '{}'", line, column, cell)
+            }
+            MazeError::MissingGoal => write!(f, "el maze no tiene salida 'g'"),
+            MazeError::MissingWall { line, column } => {
+                write!(f, "línea {}, columna {}: hueco en la pared exterior", line, column)
+            }
+            MazeError::SpawnBlocked { line, column } => {
+                write!(f, "línea {}, columna {}: el jugador aparece dentro de una pared", line, column)
+            }
+            MazeError::NoPath { line, column } => {
+                write!(f, "línea {}, columna {}: no hay camino hasta la salida", line, column)
+            }
+            MazeError::UnreachableGoal { line, column } => {
+                write!(f, "línea {}, columna {}: salida inalcanzable desde la aparición", line, column)
+            }
+            MazeError::UnreachableKey { line, column, key } => {
+                write!(f, "línea {}, columna {}: la llave de {} es inalcanzable desde la aparición", line, column, key.name())
+            }
+            MazeError::MultipleGoals { count } => {
+                write!(f, "hay {} salidas alcanzables, debe haber una sola", count)
+            }
+            MazeError::DeadZone { line, column, cells } => {
+                write!(f, "línea {}, columna {}: zona muerta de {} celdas sin acceso", line, column, cells)
+            }
+            MazeError::HiddenSprite { line, column, texture } => {
+                write!(f, "línea {}, columna {}: el sprite '{}' está dentro de una pared", line, column, texture)
+            }
+            MazeError::DoorWithoutFrame { line, column } => {
+                write!(f, "línea {}, columna {}: la puerta necesita paredes a ambos lados", line, column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MazeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MazeError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub fn load_maze(filename: &str) -> Result<Level, MazeError> {
+    let text = fs::read_to_string(filename).map_err(|source| MazeError::Io {
+        path: filename.to_string(),
+        source,
+    })?;
+    let level = parse_level(&text, filename)?;
+
+    // Solo los errores impiden jugar; las advertencias son para los diseñadores
+    match validate(&level).errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(level),
+    }
+}
+
+pub fn parse_level(text: &str, default_name: &str) -> Result<Level, MazeError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let (header, grid, grid_line) = match lines.iter().position(|line| line.trim() == "---") {
+        Some(split) => (&lines[..split], &lines[split + 1..], split + 2),
+        None => (&lines[..0], &lines[..], 1),
+    };
+
+    let mut level = Level::from_maze(default_name, Vec::new());
+    level.grid_line = grid_line;
+
+    let mut spawn_angle = None;
+    let mut floor = 'f';
+    let mut ceiling = 'c';
+    let mut extra_goals = Vec::new();
+    let mut switch_lines = Vec::new();
+    let mut key_lines = Vec::new();
+    let mut lock_lines = Vec::new();
+    let mut enemy_lines = Vec::new();
+    let mut void_line = 0;
+    let mut floor_line = 0;
+    let mut patches = Vec::new(); // (piso?, textura, región, línea)
+    let mut ceiling_line = 0;
+    let mut texture_lines = Vec::new();
+
+    for (index, line) in header.iter().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let invalid = |message: String| MazeError::InvalidHeader { line: line_number, message };
+
+        let Some((key, value)) = line.split_once(':') else {
+            return Err(invalid(format!("se esperaba 'clave: valor', se encontró '{}'", line.trim())));
+        };
+        let value = value.trim();
+
+        match key.trim() {
+            "name" => level.name = value.to_string(),
+            "music" => level.music = Some(value.to_string()),
+            "par_time" => {
+                let par = value.parse::<f32>().map_err(|_| invalid(format!("par_time no es un número: '{}'", value)))?;
+                level.par_time = Some(par);
+            }
+            "player_radius" => {
+                // Con medio bloque o más el jugador ya no entra en un pasillo
+                let radius = value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|radius| *radius > 0.0 && *radius < 0.5)
+                    .ok_or_else(|| invalid(format!("player_radius debe ser un número entre 0 y 0.5: '{}'", value)))?;
+                level.player_radius = radius;
+            }
+            "spawn_angle" => {
+                let degrees = value.parse::<f32>().map_err(|_| invalid(format!("spawn_angle no es un número: '{}'", value)))?;
+                spawn_angle = Some(degrees.to_radians());
+            }
+            key @ ("floor" | "ceiling") => {
+                let (texture, region) = parse_surface(value).ok_or_else(|| {
+                    invalid(format!("se esperaba '{}: X [columna, fila [> columna, fila]]': '{}'", key, value))
+                })?;
+                let is_floor = key == "floor";
+                match region {
+                    Some(region) => patches.push((is_floor, texture, region, line_number)),
+                    None if is_floor => (floor, floor_line) = (texture, line_number),
+                    None => (ceiling, ceiling_line) = (texture, line_number),
+                }
+            }
+            "open" => {
+                level.open = value.parse::<bool>().map_err(|_| invalid(format!("open debe ser true o false: '{}'", value)))?;
+            }
+            "void" => {
+                level.void = match value {
+                    "abierto" => Void::Open,
+                    _ => {
+                        let cell = parse_char(value)
+                            .ok_or_else(|| invalid(format!("void debe ser 'abierto' o una textura: '{}'", value)))?;
+                        Void::Solid(cell)
+                    }
+                };
+                void_line = line_number;
+            }
+            "texture" => {
+                let (ch, path) = value
+                    .split_once(char::is_whitespace)
+                    .and_then(|(ch, path)| Some((parse_char(ch)?, path.trim())))
+                    .ok_or_else(|| invalid("se esperaba 'texture: X ruta'".to_string()))?;
+                level.palette.push((ch, path.to_string()));
+                texture_lines.push(line_number);
+            }
+            "goal" => {
+                let cell = parse_cell(value).ok_or_else(|| invalid(format!("goal debe ser 'columna, fila': '{}'", value)))?;
+                extra_goals.push(cell);
+            }
+            "sprite" => {
+                let sprite = parse_sprite(value)
+                    .ok_or_else(|| invalid(format!("se esperaba 'sprite: X columna, fila [escala] [altura]': '{}'", value)))?;
+                level.sprites.push(sprite);
+            }
+            "switch" => {
+                let link = value
+                    .split_once('>')
+                    .and_then(|(switch, door)| Some((parse_cell(switch)?, parse_cell(door)?)))
+                    .ok_or_else(|| invalid(format!("se esperaba 'switch: columna, fila > columna, fila': '{}'", value)))?;
+                level.switches.push(link);
+                switch_lines.push(line_number);
+            }
+            "key" => {
+                let key = parse_key(value)
+                    .ok_or_else(|| invalid(format!("se esperaba 'key: color columna, fila': '{}'", value)))?;
+                level.keys.push(key);
+                key_lines.push(line_number);
+            }
+            "lock" => {
+                let lock = parse_key(value)
+                    .ok_or_else(|| invalid(format!("se esperaba 'lock: color columna, fila': '{}'", value)))?;
+                level.locks.push(lock);
+                lock_lines.push(line_number);
+            }
+            "enemy" => {
+                let enemy = parse_enemy(value).ok_or_else(|| {
+                    invalid(format!("se esperaba 'enemy: tipo columna, fila [> columna, fila]...': '{}'", value))
+                })?;
+                level.enemies.push(enemy);
+                enemy_lines.push(line_number);
+            }
+            other => return Err(invalid(format!("clave desconocida '{}'", other))),
+        }
+    }
+
+    // Las texturas propias tienen que existir: el TextureManager no sabe
+    // seguir sin ellas
+    for ((_, path), &line) in level.palette.iter().zip(&texture_lines) {
+        if !Path::new(path).is_file() {
+            let message = format!("no existe la textura '{}'", path);
+            return Err(MazeError::InvalidHeader { line, message });
+        }
+    }
+
+    // Piso y techo usan las texturas de superficie, las de pared o las de la paleta
+    let in_palette = |cell: char| level.palette.iter().any(|(ch, _)| *ch == cell);
+    let mut textures = vec![(floor, floor_line, "floor"), (ceiling, ceiling_line, "ceiling")];
+    for &(is_floor, texture, _, line) in &patches {
+        textures.push((texture, line, if is_floor { "floor" } else { "ceiling" }));
+    }
+    for (cell, line, key) in textures {
+        if !SURFACE_CELLS.contains(&cell) && !WALL_CELLS.contains(&cell) && !in_palette(cell) {
+            let message = format!("{} '{}' no es una textura conocida", key, cell);
+            return Err(MazeError::InvalidHeader { line, message });
+        }
+    }
+
+    // El vacío sólido se dibuja con la textura de una pared
+    if let Void::Solid(cell) = level.void {
+        if !WALL_CELLS.contains(&cell) && !in_palette(cell) {
+            let message = format!("void '{}' no es una textura de pared", cell);
+            return Err(MazeError::InvalidHeader { line: void_line, message });
+        }
+    }
+
+    // Las líneas vacías al final del archivo no son filas del maze
+    let mut maze: Maze = grid.iter().map(|line| line.chars().collect()).collect();
+    while maze.last().is_some_and(|row| row.is_empty()) {
+        maze.pop();
+    }
+
+    // Los espacios sobrantes a la derecha de la primera fila no cuentan como celdas
+    let expected = maze.first().map_or(0, |row| row.len());
+    for row in maze.iter_mut() {
+        while row.len() > expected && row.last() == Some(&' ') {
+            row.pop();
+        }
+    }
+
+    let mut marker_angle = None;
+
+    for (j, row) in maze.iter_mut().enumerate() {
+        for (i, cell) in row.iter_mut().enumerate() {
+            let facing = match *cell {
+                '@' => None,
+                '>' => Some(0.0),
+                'v' => Some(PI / 2.0),
+                '<' => Some(PI),
+                '^' => Some(3.0 * PI / 2.0),
+                _ => continue,
+            };
+            level.spawn = (i, j);
+            marker_angle = facing;
+            *cell = ' ';
+        }
+    }
+
+    for (i, j) in extra_goals {
+        if let Some(cell) = maze.get_mut(j).and_then(|row| row.get_mut(i)) {
+            *cell = 'g';
+        }
+    }
+
+    // Los enlaces tienen que ir de un interruptor a una puerta
+    for (&(switch, door), &line) in level.switches.iter().zip(&switch_lines) {
+        let cell = |(i, j): (usize, usize)| cell_at(&maze, i as isize, j as isize);
+        if cell(switch) != Some(SWITCH) || cell(door) != Some(DOOR) {
+            let message = format!("switch {:?} > {:?} no va de un interruptor 's' a una puerta 'D'", switch, door);
+            return Err(MazeError::InvalidHeader { line, message });
+        }
+    }
+
+    // Las llaves van en el piso y los candados en salidas o puertas que
+    // tengan su llave en el mismo nivel
+    for (&(key, cell), &line) in level.keys.iter().zip(&key_lines) {
+        let (i, j) = (cell.0 as isize, cell.1 as isize);
+        if !is_walkable(cell_at(&maze, i, j)) || cell_at(&maze, i, j) == Some(DOOR) {
+            let message = format!("la llave de {} en {:?} no está sobre el piso", key.name(), cell);
+            return Err(MazeError::InvalidHeader { line, message });
+        }
+    }
+    for (&(key, cell), &line) in level.locks.iter().zip(&lock_lines) {
+        let target = cell_at(&maze, cell.0 as isize, cell.1 as isize);
+        if target != Some('g') && target != Some(DOOR) {
+            let message = format!("lock {:?} no está sobre una salida 'g' ni una puerta 'D'", cell);
+            return Err(MazeError::InvalidHeader { line, message });
+        }
+        if !level.keys.iter().any(|&(k, _)| k == key) {
+            let message = format!("el candado de {} no tiene su llave en el nivel", key.name());
+            return Err(MazeError::InvalidHeader { line, message });
+        }
+    }
+
+    // Los enemigos aparecen y patrullan sobre el piso
+    for (enemy, &line) in level.enemies.iter().zip(&enemy_lines) {
+        if let Some(&cell) = enemy.route.iter().find(|&&(i, j)| !is_walkable(cell_at(&maze, i as isize, j as isize))) {
+            let message = format!("la ruta de {} pasa por {:?}, que no es piso", enemy.kind.config_name(), cell);
+            return Err(MazeError::InvalidHeader { line, message });
+        }
+    }
+
+    level.goals = find_cells(&maze, 'g');
+    level.surfaces = Surfaces::uniform(&maze, floor, ceiling);
+
+    // Piso y techo propios de algunas celdas, en el orden de la cabecera
+    for (is_floor, texture, (from, to), line) in patches {
+        let layer = if is_floor { &mut level.surfaces.floor } else { &mut level.surfaces.ceiling };
+        for j in from.1.min(to.1)..=from.1.max(to.1) {
+            for i in from.0.min(to.0)..=from.0.max(to.0) {
+                let Some(cell) = layer.get_mut(j).and_then(|row| row.get_mut(i)) else {
+                    let message = format!("la celda {:?} está fuera de la cuadrícula", (i, j));
+                    return Err(MazeError::InvalidHeader { line, message });
+                };
+                *cell = texture;
+            }
+        }
+    }
+
+    // Los interruptores se ven en el piso
+    for (i, j) in find_cells(&maze, SWITCH) {
+        level.surfaces.floor[j][i] = SWITCH;
+    }
+    level.spawn_angle = marker_angle.or(spawn_angle).unwrap_or(PI / 3.0);
+    level.maze = maze;
+
+    Ok(level)
+}
+
+pub fn find_cells(maze: &Maze, target: char) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for (j, row) in maze.iter().enumerate() {
+        for (i, &cell) in row.iter().enumerate() {
+            if cell == target {
+                cells.push((i, j));
+            }
+        }
+    }
+    cells
+}
+
+fn parse_char(value: &str) -> Option<char> {
+    let mut chars = value.trim().chars();
+    let ch = chars.next()?;
+    chars.next().is_none().then_some(ch)
+}
+
+// "color columna, fila" -> llave o candado en esa celda
+fn parse_key(value: &str) -> Option<(KeyColor, (usize, usize))> {
+    let (color, cell) = value.split_once(char::is_whitespace)?;
+    Some((KeyColor::parse(color)?, parse_cell(cell)?))
+}
+
+// "tipo columna, fila > columna, fila > ..." -> enemigo con su ruta de patrulla
+fn parse_enemy(value: &str) -> Option<EnemySpawn> {
+    let mut parts = value.split('>');
+    let (kind, first) = parts.next()?.trim().split_once(char::is_whitespace)?;
+    let mut route = vec![parse_cell(first)?];
+    for cell in parts {
+        route.push(parse_cell(cell)?);
+    }
+    Some(EnemySpawn { kind: EnemyKind::parse(kind)?, route })
+}
+
+// "X [columna, fila [> columna, fila]]" -> textura y, si tiene, el rectángulo
+// de celdas donde va (sin rectángulo es para todo el nivel)
+fn parse_surface(value: &str) -> Option<(char, Option<CellRect>)> {
+    let (texture, region) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+    let texture = parse_char(texture)?;
+    if region.trim().is_empty() {
+        return Some((texture, None));
+    }
+    let (from, to) = match region.split_once('>') {
+        Some((from, to)) => (parse_cell(from)?, parse_cell(to)?),
+        None => (parse_cell(region)?, parse_cell(region)?),
+    };
+    Some((texture, Some((from, to))))
+}
+
+// "columna, fila" -> (columna, fila)
+fn parse_cell(value: &str) -> Option<(usize, usize)> {
+    let (i, j) = value.split_once(',')?;
+    Some((i.trim().parse().ok()?, j.trim().parse().ok()?))
+}
+
+// "X columna, fila [escala] [altura]" -> sprite en el centro de la celda
+fn parse_sprite(value: &str) -> Option<Sprite> {
+    let (ch, rest) = value.split_once(char::is_whitespace)?;
+    let (i, rest) = rest.split_once(',')?;
+    let mut numbers = rest.split_whitespace();
+    let j: usize = numbers.next()?.parse().ok()?;
+    let i: usize = i.trim().parse().ok()?;
+
+    let mut sprite = Sprite::new(Vector2::new(i as f32 + 0.5, j as f32 + 0.5), parse_char(ch)?);
+    if let Some(scale) = numbers.next() {
+        sprite.scale = scale.parse().ok()?;
+    }
+    if let Some(offset) = numbers.next() {
+        sprite.offset = offset.parse().ok()?;
+    }
+    numbers.next().is_none().then_some(sprite)
+}
+
+// Celda en la columna i, fila j; None si cae fuera de la cuadrícula o de una fila corta
+pub fn cell_at(maze: &Maze, i: isize, j: isize) -> Option<char> {
+    if i < 0 || j < 0 {
+        return None;
+    }
+    maze.get(j as usize).and_then(|row| row.get(i as usize)).copied()
+}
+
+// Celda (columna, fila) que contiene un punto del mundo; puede quedar fuera de la cuadrícula
+pub fn world_to_cell(pos: Vector2, block_size: f32) -> (isize, isize) {
+    ((pos.x / block_size).floor() as isize, (pos.y / block_size).floor() as isize)
+}
+
+// Se puede caminar por la celda: espacio vacío, la salida, un interruptor o
+// una puerta (que se puede abrir)
+pub fn is_walkable(cell: Option<char>) -> bool {
+    matches!(cell, Some(' ') | Some('g') | Some(SWITCH) | Some(DOOR))
+}
+
+// Las cuatro celdas vecinas (pueden quedar fuera de la cuadrícula)
+pub fn neighbors(i: usize, j: usize) -> [(isize, isize); 4] {
+    let (i, j) = (i as isize, j as isize);
+    [(i + 1, j), (i - 1, j), (i, j + 1), (i, j - 1)]
+}
+
+// Ancho de la fila más larga del maze
+pub fn maze_width(maze: &Maze) -> usize {
+    maze.iter().map(|row| row.len()).max().unwrap_or(0)
+}
+
+// Texturas de piso y techo de cada celda, guardadas junto al maze
+pub struct Surfaces {
+    pub floor: Vec<Vec<char>>,
+    pub ceiling: Vec<Vec<char>>,
+}
+
+impl Surfaces {
+    // Misma textura de piso y techo en todas las celdas del maze
+    pub fn uniform(maze: &Maze, floor: char, ceiling: char) -> Self {
+        Surfaces {
+            floor: maze.iter().map(|row| vec![floor; row.len()]).collect(),
+            ceiling: maze.iter().map(|row| vec![ceiling; row.len()]).collect(),
+        }
+    }
+
+    pub fn floor_at(&self, i: isize, j: isize) -> Option<char> {
+        cell_at(&self.floor, i, j)
+    }
 
-    reader
-        .lines()
-        .map(|line| line.unwrap().chars().collect())
-        .collect()
-}
\ No newline at end of file
+    pub fn ceiling_at(&self, i: isize, j: isize) -> Option<char> {
+        cell_at(&self.ceiling, i, j)
+    }
+}
@@ -1,5 +1,6 @@
 // menu.rs
 use raylib::prelude::*;
+use crate::input::{Action, InputMap};
 
 // Estructura para manejar las imágenes del menú
 pub struct MenuImages {
@@ -28,7 +29,12 @@ impl MenuImages {
     }
 }
 
-pub fn render_menu(d: &mut RaylibDrawHandle, menu_images: Option<&MenuImages>) {
+pub fn render_menu(
+  d: &mut RaylibDrawHandle,
+  menu_images: Option<&MenuImages>,
+  error: Option<&str>,
+  input: &InputMap,
+) {
   for y in 0..d.get_screen_height() {
     let gradient_ratio = y as f32 / d.get_screen_height() as f32;
     let color = Color::new(
@@ -58,9 +64,9 @@ pub fn render_menu(d: &mut RaylibDrawHandle, menu_images: Option<&MenuImages>) {
   let card_y = 200;
   
   let characters = [
-    ("INFANCIA", "Alegría", Color::new(255, 215, 0, 255), "[1]"),
-    ("ADOLESCENCIA", "Ansiedad", Color::new(255, 140, 0, 255), "[2]"),
-    ("ADULTEZ", "Envidia", Color::new(138, 43, 226, 255), "[3]"),
+    ("INFANCIA", "Alegría", Color::new(255, 215, 0, 255), input.label(Action::Level1)),
+    ("ADOLESCENCIA", "Ansiedad", Color::new(255, 140, 0, 255), input.label(Action::Level2)),
+    ("ADULTEZ", "Envidia", Color::new(138, 43, 226, 255), input.label(Action::Level3)),
   ];
 
   let textures = if let Some(images) = menu_images {
@@ -137,9 +143,36 @@ pub fn render_menu(d: &mut RaylibDrawHandle, menu_images: Option<&MenuImages>) {
     d.draw_text(&format!("Presiona {}", key), key_x, key_bg_y + 12, 16, Color::WHITE);
   }
   
+  let move_text = format!(
+    "🎮 Controles: {}{}{}{} para moverse, {} para cambiar vista 2D/3D, {} para pedir una pista, {} para pausar",
+    input.label(Action::MoveForward),
+    input.label(Action::StrafeLeft),
+    input.label(Action::MoveBackward),
+    input.label(Action::StrafeRight),
+    input.label(Action::ToggleMap),
+    input.label(Action::ToggleHint),
+    input.label(Action::Pause),
+  );
+  let controls_text = format!(
+    "🕹 Controles: {}  ({} para cambiar, {} para salir)",
+    input.controls.name(),
+    input.label(Action::ToggleControls),
+    input.label(Action::Quit),
+  );
+  let endless_text = format!(
+    "♾ Presiona {} para el modo infinito: un laberinto nuevo en cada partida",
+    input.label(Action::Endless),
+  );
+  let continue_text = format!(
+    "💾 Presiona {} para continuar la última partida guardada (se guarda al pausar)",
+    input.label(Action::Continue),
+  );
   let instructions = [
-    "🎮 Controles: WASD para moverse, M para cambiar vista 2D/3D",
-    "🎯 Objetivo: Encuentra la salida (zona gris) en cada laberinto"
+    move_text.as_str(),
+    controls_text.as_str(),
+    "🎯 Objetivo: Encuentra la salida (zona gris) en cada laberinto",
+    endless_text.as_str(),
+    continue_text.as_str(),
   ];
   
   let inst_start_y = card_y + card_height + 40;
@@ -150,6 +183,14 @@ pub fn render_menu(d: &mut RaylibDrawHandle, menu_images: Option<&MenuImages>) {
     d.draw_text(instruction, inst_x + 1, inst_y + 1, 18, Color::new(0, 0, 0, 100));
     d.draw_text(instruction, inst_x, inst_y, 18, Color::new(220, 220, 255, 255));
   }
+
+  //error al cargar el último laberinto elegido
+  if let Some(error) = error {
+    let error_y = inst_start_y + (instructions.len() as i32) * 30 + 10;
+    let error_x = (d.get_screen_width() - measure_text(error, 18)) / 2;
+    d.draw_rectangle(error_x - 10, error_y - 6, measure_text(error, 18) + 20, 30, Color::new(120, 0, 0, 220));
+    d.draw_text(error, error_x, error_y, 18, Color::new(255, 200, 200, 255));
+  }
 }
 }
 
@@ -159,7 +200,14 @@ fn measure_text(text: &str, font_size: i32) -> i32 {
 }
 
 //pantalla de victoria 
-pub fn render_victory_screen(d: &mut RaylibDrawHandle) {
+pub fn render_victory_screen(
+  d: &mut RaylibDrawHandle,
+  time: f32,
+  par_time: Option<f32>,
+  steps: usize,
+  par_steps: Option<usize>,
+  input: &InputMap,
+) {
   for y in 0..d.get_screen_height() {
     let gradient_ratio = y as f32 / d.get_screen_height() as f32;
     let color = Color::new(
@@ -176,11 +224,55 @@ pub fn render_victory_screen(d: &mut RaylibDrawHandle) {
   
   d.draw_text(title, title_x + 3, 253, 50, Color::new(0, 0, 0, 120));
   d.draw_text(title, title_x, 250, 50, Color::new(50, 205, 50, 255)); // Verde brillante
+
+  //tiempo del nivel comparado con el par
+  let time_text = match par_time {
+    Some(par) => format!("Tiempo: {:.1} s  (par {:.0} s)", time, par),
+    None => format!("Tiempo: {:.1} s", time),
+  };
+  let time_color = match par_time {
+    Some(par) if time <= par => Color::new(255, 215, 0, 255), // Dorado si se venció el par
+    _ => Color::new(200, 200, 255, 255),
+  };
+  let time_x = (d.get_screen_width() - measure_text(&time_text, 25)) / 2;
+  d.draw_text(&time_text, time_x, 330, 25, time_color);
+
+  //pasos dados comparados con el camino más corto
+  let steps_text = match par_steps {
+    Some(par) => format!("Pasos: {}  (par {})", steps, par),
+    None => format!("Pasos: {}", steps),
+  };
+  let steps_x = (d.get_screen_width() - measure_text(&steps_text, 25)) / 2;
+  d.draw_text(&steps_text, steps_x, 365, 25, Color::new(200, 200, 255, 255));
   
-  let instruction = "Presiona [ENTER] para volver al menú";
-  let inst_x = (d.get_screen_width() - measure_text(instruction, 25)) / 2;
-  d.draw_text(instruction, inst_x + 2, 402, 25, Color::new(0, 0, 0, 100)); // Sombra
-  d.draw_text(instruction, inst_x, 400, 25, Color::WHITE);
+  let instruction = format!("Presiona {} para volver al menú", input.label(Action::Select));
+  let inst_x = (d.get_screen_width() - measure_text(&instruction, 25)) / 2;
+  d.draw_text(&instruction, inst_x + 2, 402, 25, Color::new(0, 0, 0, 100)); // Sombra
+  d.draw_text(&instruction, inst_x, 400, 25, Color::WHITE);
+}
+
+//pantalla de pausa
+pub fn render_pause_screen(d: &mut RaylibDrawHandle, level_name: &str, input: &InputMap) {
+  d.clear_background(Color::new(15, 15, 35, 255));
+
+  let title = "PAUSA";
+  let title_x = (d.get_screen_width() - measure_text(title, 50)) / 2;
+  d.draw_text(title, title_x + 3, 253, 50, Color::new(0, 0, 0, 120));
+  d.draw_text(title, title_x, 250, 50, Color::new(255, 215, 0, 255));
+
+  let name_x = (d.get_screen_width() - measure_text(level_name, 25)) / 2;
+  d.draw_text(level_name, name_x, 330, 25, Color::new(200, 200, 255, 255));
+
+  let options = [
+    format!("Presiona {} para continuar", input.label(Action::Pause)),
+    format!("Presiona {} para volver al menú", input.label(Action::Back)),
+  ];
+  for (i, option) in options.iter().enumerate() {
+    let option_x = (d.get_screen_width() - measure_text(option, 25)) / 2;
+    let option_y = 400 + (i as i32) * 40;
+    d.draw_text(option, option_x + 2, option_y + 2, 25, Color::new(0, 0, 0, 100));
+    d.draw_text(option, option_x, option_y, 25, Color::WHITE);
+  }
 }
 
 
@@ -2,99 +2,94 @@
 
 use raylib::prelude::*;
 use std::f32::consts::PI;
-use crate::maze::Maze;
+use crate::input::{Action, ControlScheme, InputMap};
+use crate::collision;
+use crate::door::Doors;
+use crate::key::Inventory;
+use crate::maze::{Level, Maze, cell_at, world_to_cell};
 
 pub struct Player {
     pub pos: Vector2,
     pub a: f32,
     pub fov: f32,
+    pub radius: f32, // radio del círculo de colisión, en unidades del mundo
+    pub inventory: Inventory,
 }
 
 impl Player {
-    pub fn is_position_free(&self, maze: &Maze, new_pos: Vector2, block_size: f32) -> bool {
-        let player_radius = block_size * 0.3;
-        
-        let positions_to_check = [
-            Vector2::new(new_pos.x - player_radius, new_pos.y - player_radius), 
-            Vector2::new(new_pos.x + player_radius, new_pos.y - player_radius),
-            Vector2::new(new_pos.x - player_radius, new_pos.y + player_radius), 
-            Vector2::new(new_pos.x + player_radius, new_pos.y + player_radius), 
-            new_pos, 
-        ];
-        
-        for pos in &positions_to_check {
-            let grid_x = (pos.x / block_size) as usize;
-            let grid_y = (pos.y / block_size) as usize;
-
-            if grid_y >= maze.len() || grid_x >= maze[0].len() {
-                return false; //fuera del maze
-            }
-            
-            //verificar si hay pared 
-            let cell = maze[grid_y][grid_x];
-            if cell != ' ' && cell != 'g' {
-                return false; //si hay
-            }
-        }
-        
-        true //posiciones libres
+    // Celda (columna, fila) donde está parado el jugador
+    pub fn grid_cell(&self, block_size: f32) -> (isize, isize) {
+        world_to_cell(self.pos, block_size)
     }
-    
-    pub fn has_reached_goal(&self, maze: &Maze, block_size: f32) -> bool {
-        let grid_x = (self.pos.x / block_size) as usize;
-        let grid_y = (self.pos.y / block_size) as usize;
+
+    // Una salida con candado no cuenta hasta tener su llave
+    pub fn has_reached_goal(&self, level: &Level, block_size: f32) -> bool {
+        let (grid_x, grid_y) = self.grid_cell(block_size);
         
-        if grid_y < maze.len() && grid_x < maze[0].len() {
-            maze[grid_y][grid_x] == 'g'
-        } else {
-            false
-        }
+        cell_at(&level.maze, grid_x, grid_y) == Some('g')
+            && level.missing_key((grid_x as usize, grid_y as usize), &self.inventory).is_none()
     }
 }
 
-pub fn process_events(player: &mut Player, rl: &RaylibHandle, maze: &Maze, block_size: f32) {
-    const MOVE_SPEED: f32 = 10.0;
-    const ROTATION_SPEED: f32 = PI / 10.0;
-    const MOUSE_SENSITIVITY: f32 = 0.003;
-
-    let mouse_delta = rl.get_mouse_delta(); 
-    player.a += -mouse_delta.x * MOUSE_SENSITIVITY; 
+// Velocidades en unidades del mundo por segundo y radianes por segundo
+const MOVE_SPEED: f32 = 300.0;
+const ROTATION_SPEED: f32 = 3.0;
 
-    if player.a < 0.0 {
-        player.a += 2.0 * PI;
-    } else if player.a > 2.0 * PI {
-        player.a -= 2.0 * PI;
+// Girar con el mouse: el desplazamiento ya es independiente del framerate,
+// así que se aplica una vez por cuadro y no en cada paso de la simulación.
+// Un ángulo mayor gira la vista hacia la derecha de la pantalla.
+pub fn process_mouse(player: &mut Player, rl: &RaylibHandle, input: &InputMap) {
+    if input.controls != ControlScheme::Modern {
+        return;
     }
+    player.a += input.look_axis(rl);
+    player.a = player.a.rem_euclid(2.0 * PI);
+}
+
+// Avanza la simulación del jugador `dt` segundos según las acciones mantenidas
+pub fn process_events(
+    player: &mut Player,
+    rl: &RaylibHandle,
+    maze: &Maze,
+    doors: &Doors,
+    block_size: f32,
+    dt: f32,
+    input: &InputMap,
+) {
+    let left = input.is_down(rl, Action::StrafeLeft);
+    let right = input.is_down(rl, Action::StrafeRight);
 
-    if rl.is_key_down(KeyboardKey::KEY_LEFT) || rl.is_key_down(KeyboardKey::KEY_A) {
-        player.a += ROTATION_SPEED;
+    // En el esquema clásico el desplazamiento lateral gira igual que las flechas
+    let turn_keys = input.controls == ControlScheme::Tank;
+    if input.is_down(rl, Action::TurnLeft) || (turn_keys && left) {
+        player.a -= ROTATION_SPEED * dt;
     }
-    if rl.is_key_down(KeyboardKey::KEY_RIGHT) || rl.is_key_down(KeyboardKey::KEY_D) {
-        player.a -= ROTATION_SPEED;
+    if input.is_down(rl, Action::TurnRight) || (turn_keys && right) {
+        player.a += ROTATION_SPEED * dt;
     }
+    player.a = player.a.rem_euclid(2.0 * PI);
 
-    let mut new_pos = player.pos;
+    // Vector de movimiento combinado: adelante/atrás más desplazamiento lateral
+    let forward = Vector2::new(player.a.cos(), player.a.sin());
+    let side = Vector2::new(-player.a.sin(), player.a.cos()); // hacia la derecha de la vista
+    let mut motion = Vector2::zero();
 
-    if rl.is_key_down(KeyboardKey::KEY_UP) || rl.is_key_down(KeyboardKey::KEY_W) {
-        new_pos.x += MOVE_SPEED * player.a.cos();
-        new_pos.y += MOVE_SPEED * player.a.sin();
+    if input.is_down(rl, Action::MoveForward) {
+        motion += forward;
+    }
+    if input.is_down(rl, Action::MoveBackward) {
+        motion -= forward;
     }
-    if rl.is_key_down(KeyboardKey::KEY_DOWN) || rl.is_key_down(KeyboardKey::KEY_S) {
-        new_pos.x -= MOVE_SPEED * player.a.cos();
-        new_pos.y -= MOVE_SPEED * player.a.sin();
+    if !turn_keys && left {
+        motion -= side;
+    }
+    if !turn_keys && right {
+        motion += side;
     }
 
-    if player.is_position_free(maze, new_pos, block_size) {
-        player.pos = new_pos;
-    } else {
-        let new_pos_x = Vector2::new(new_pos.x, player.pos.y);
-        if player.is_position_free(maze, new_pos_x, block_size) {
-            player.pos = new_pos_x;
-        } else {
-            let new_pos_y = Vector2::new(player.pos.x, new_pos.y);
-            if player.is_position_free(maze, new_pos_y, block_size) {
-                player.pos = new_pos_y;
-            }
-        }
+    // En diagonal no se avanza más rápido
+    if motion.length() > 1.0 {
+        motion = motion.normalized();
     }
-}
\ No newline at end of file
+    player.pos = collision::move_and_slide(maze, doors, player.pos, motion * (MOVE_SPEED * dt), player.radius, block_size);
+}
@@ -0,0 +1,99 @@
+// save.rs
+
+use raylib::prelude::Vector2;
+use std::fs;
+use std::io;
+
+use crate::key::KeyColor;
+
+pub const SAVE_FILE: &str = "partida.sav";
+
+// Partida guardada: en qué nivel, dónde estaba el jugador y qué llevaba.
+// Los enemigos y las puertas vuelven a su estado inicial al cargarla.
+pub struct SaveData {
+    pub level_file: String,
+    pub pos: Vector2,           // en celdas
+    pub angle: f32,             // radianes
+    pub ticks: u32,             // pasos de simulación jugados
+    pub steps: usize,           // celdas recorridas
+    pub keys: Vec<KeyColor>,
+    pub explored: Vec<String>,  // filas de Explored::to_rows
+}
+
+impl SaveData {
+    // A diferencia de los controles, una partida con errores no se puede continuar
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut save = SaveData {
+            level_file: String::new(),
+            pos: Vector2::zero(),
+            angle: 0.0,
+            ticks: 0,
+            steps: 0,
+            keys: Vec::new(),
+            explored: Vec::new(),
+        };
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            save.apply_line(line).map_err(|message| format!("{} línea {}: {}", path, index + 1, message))?;
+        }
+
+        if save.level_file.is_empty() {
+            return Err(format!("{}: falta el nivel", path));
+        }
+        Ok(save)
+    }
+
+    fn apply_line(&mut self, line: &str) -> Result<(), String> {
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("se esperaba 'clave = valor', se encontró '{}'", line))?;
+        let (key, value) = (key.trim(), value.trim());
+        let number = |value: &str| value.trim().parse::<f32>().map_err(|_| format!("{} no es un número: '{}'", key, value));
+
+        match key {
+            "level" => self.level_file = value.to_string(),
+            "pos" => {
+                let (x, y) = value.split_once(',').ok_or_else(|| format!("pos espera x, y: '{}'", value))?;
+                self.pos = Vector2::new(number(x)?, number(y)?);
+            }
+            "angle" => self.angle = number(value)?,
+            "ticks" => self.ticks = value.parse().map_err(|_| format!("ticks no es un entero: '{}'", value))?,
+            "steps" => self.steps = value.parse().map_err(|_| format!("steps no es un entero: '{}'", value))?,
+            "keys" => {
+                self.keys = value
+                    .split(',')
+                    .filter(|name| !name.trim().is_empty())
+                    .map(|name| KeyColor::parse(name).ok_or_else(|| format!("llave desconocida '{}'", name.trim())))
+                    .collect::<Result<Vec<KeyColor>, String>>()?;
+            }
+            "explored" => self.explored.push(value.to_string()),
+            _ => return Err(format!("clave desconocida '{}'", key)),
+        }
+
+        Ok(())
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut text = String::from("# Inside Out Maze - partida guardada\n");
+        text.push_str(&format!("level = {}\n", self.level_file));
+        text.push_str(&format!("pos = {}, {}\n", self.pos.x, self.pos.y));
+        text.push_str(&format!("angle = {}\n", self.angle));
+        text.push_str(&format!("ticks = {}\n", self.ticks));
+        text.push_str(&format!("steps = {}\n", self.steps));
+
+        let keys: Vec<&str> = self.keys.iter().map(|key| key.config_name()).collect();
+        text.push_str(&format!("keys = {}\n", keys.join(", ")));
+
+        // Una línea por fila del maze: '#' explorada, '.' sin explorar
+        for row in &self.explored {
+            text.push_str(&format!("explored = {}\n", row));
+        }
+
+        fs::write(path, text)
+    }
+}
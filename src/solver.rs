@@ -0,0 +1,162 @@
+// solver.rs
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::key::Inventory;
+use crate::maze::{Level, Maze, cell_at, is_walkable, neighbors};
+use crate::validate::{KeyOrder, flood_with_keys};
+
+// Camino más corto desde `start` hasta la salida 'g' más cercana, incluyendo
+// ambas celdas. Los candados cuya llave no está en `inventory` no se cruzan.
+// None si no hay salida alcanzable
+pub fn shortest_path(level: &Level, inventory: &Inventory, start: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+    search(level, inventory, start, |(i, j)| level.maze[j][i] == 'g')
+}
+
+// Camino más corto entre dos celdas, incluyendo ambas
+pub fn path_between(
+    level: &Level,
+    inventory: &Inventory,
+    start: (usize, usize),
+    target: (usize, usize),
+) -> Option<Vec<(usize, usize)>> {
+    search(level, inventory, start, |cell| cell == target)
+}
+
+// Pasos para juntar las llaves en el orden en que las consigue la validación
+// y después llegar a la salida
+pub fn par_steps(level: &Level) -> Option<usize> {
+    let mut steps = 0;
+    let mut current = level.spawn;
+    let mut inventory = Inventory::default();
+    for (color, key) in key_order(level) {
+        steps += path_between(level, &inventory, current, key)?.len() - 1;
+        inventory.add(color);
+        current = key;
+    }
+    Some(steps + shortest_path(level, &inventory, current)?.len() - 1)
+}
+
+// Llaves alcanzables en el orden en que hay que juntarlas
+pub fn key_order(level: &Level) -> KeyOrder {
+    flood_with_keys(level).1
+}
+
+// A* entre dos celdas con distancia Manhattan como heurística. `passable`
+// decide qué celdas se pueden pisar (por ejemplo, sin puertas cerradas).
+// El camino incluye ambas celdas; None si `goal` no es alcanzable.
+pub fn astar<F>(maze: &Maze, start: (usize, usize), goal: (usize, usize), passable: F) -> Option<Vec<(usize, usize)>>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    if !passable(start.0, start.1) || !passable(goal.0, goal.1) {
+        return None;
+    }
+
+    let heuristic = |(i, j): (usize, usize)| i.abs_diff(goal.0) + j.abs_diff(goal.1);
+    let mut came_from: Vec<Vec<Option<(usize, usize)>>> = maze.iter().map(|row| vec![None; row.len()]).collect();
+    let mut cost: Vec<Vec<usize>> = maze.iter().map(|row| vec![usize::MAX; row.len()]).collect();
+    let mut open = BinaryHeap::from([Reverse((heuristic(start), start))]);
+    came_from[start.1][start.0] = Some(start);
+    cost[start.1][start.0] = 0;
+
+    while let Some(Reverse((_, (i, j)))) = open.pop() {
+        if (i, j) == goal {
+            let mut path = vec![goal];
+            let mut current = goal;
+            while current != start {
+                current = came_from[current.1][current.0]?;
+                path.push(current);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let next_cost = cost[j][i] + 1;
+        for (ni, nj) in neighbors(i, j) {
+            if cell_at(maze, ni, nj).is_none() || !passable(ni as usize, nj as usize) {
+                continue;
+            }
+            let (ni, nj) = (ni as usize, nj as usize);
+            if next_cost < cost[nj][ni] {
+                cost[nj][ni] = next_cost;
+                came_from[nj][ni] = Some((i, j));
+                open.push(Reverse((next_cost + heuristic((ni, nj)), (ni, nj))));
+            }
+        }
+    }
+
+    None
+}
+
+// BFS desde `start` hasta la primera celda que cumpla `is_target`
+fn search<F>(level: &Level, inventory: &Inventory, start: (usize, usize), is_target: F) -> Option<Vec<(usize, usize)>>
+where
+    F: Fn((usize, usize)) -> bool,
+{
+    let maze = &level.maze;
+    if !is_walkable(cell_at(maze, start.0 as isize, start.1 as isize)) {
+        return None;
+    }
+
+    // BFS: cada celda recuerda de dónde llegó para reconstruir el camino
+    let mut came_from: Vec<Vec<Option<(usize, usize)>>> = maze.iter().map(|row| vec![None; row.len()]).collect();
+    let mut queue = VecDeque::from([start]);
+    came_from[start.1][start.0] = Some(start);
+
+    while let Some((i, j)) = queue.pop_front() {
+        if is_target((i, j)) {
+            let mut path = vec![(i, j)];
+            let mut current = (i, j);
+            while current != start {
+                current = came_from[current.1][current.0]?;
+                path.push(current);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for (ni, nj) in neighbors(i, j) {
+            if !is_walkable(cell_at(maze, ni, nj)) {
+                continue;
+            }
+            let (ni, nj) = (ni as usize, nj as usize);
+            if came_from[nj][ni].is_none() && level.missing_key((ni, nj), inventory).is_none() {
+                came_from[nj][ni] = Some((i, j));
+                queue.push_back((ni, nj));
+            }
+        }
+    }
+
+    None
+}
+
+// Pista visible para el jugador: el camino y una máscara para consultarlo rápido
+pub struct Hint {
+    pub path: Vec<(usize, usize)>,
+    cells: Vec<Vec<bool>>,
+}
+
+impl Hint {
+    pub fn new(level: &Level, inventory: &Inventory, start: (usize, usize)) -> Option<Self> {
+        Self::from_path(&level.maze, shortest_path(level, inventory, start)?)
+    }
+
+    // Pista hacia una celda en particular, por ejemplo una llave
+    pub fn to_cell(level: &Level, inventory: &Inventory, start: (usize, usize), target: (usize, usize)) -> Option<Self> {
+        Self::from_path(&level.maze, path_between(level, inventory, start, target)?)
+    }
+
+    fn from_path(maze: &Maze, path: Vec<(usize, usize)>) -> Option<Self> {
+        let mut cells: Vec<Vec<bool>> = maze.iter().map(|row| vec![false; row.len()]).collect();
+        for &(i, j) in &path {
+            cells[j][i] = true;
+        }
+        Some(Hint { path, cells })
+    }
+
+    pub fn contains(&self, i: isize, j: isize) -> bool {
+        i >= 0 && j >= 0 && self.cells.get(j as usize).and_then(|row| row.get(i as usize)).copied().unwrap_or(false)
+    }
+}
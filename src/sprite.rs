@@ -0,0 +1,108 @@
+// sprite.rs
+
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+use crate::camera::Camera;
+use crate::framebuffer::Framebuffer;
+use crate::player::Player;
+use crate::textures::{TextureManager, distance_shade, shade};
+
+// Objeto del mundo dibujado como una imagen que siempre mira a la cámara
+#[derive(Clone, Copy, Debug)]
+pub struct Sprite {
+    pub pos: Vector2,  // coordenadas del mundo (en la cabecera del nivel, en celdas)
+    pub texture: char, // textura del TextureManager
+    pub scale: f32,    // altura en celdas (1.0 mide lo mismo que una pared)
+    pub offset: f32,   // altura del pie del sprite sobre el piso, en celdas
+}
+
+impl Sprite {
+    pub fn new(pos: Vector2, texture: char) -> Self {
+        Sprite { pos, texture, scale: 1.0, offset: 0.0 }
+    }
+}
+
+// Dibuja los sprites de más lejos a más cerca sobre la imagen ya renderizada.
+// Cada columna del sprite se descarta si la pared de esa columna está más cerca.
+pub fn render_sprites(
+    framebuffer: &mut Framebuffer,
+    player: &Player,
+    sprites: &[Sprite],
+    texture_manager: &TextureManager,
+    block_size: usize,
+) {
+    let camera = Camera::from_player(player, framebuffer.width, framebuffer.height);
+    let size = block_size as f32;
+
+    let mut visible: Vec<(f32, &Sprite)> = sprites
+        .iter()
+        .map(|sprite| (sprite.pos.distance_to(camera.pos), sprite))
+        .collect();
+    visible.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    for (_, sprite) in visible {
+        render_sprite(framebuffer, &camera, sprite, texture_manager, size);
+    }
+}
+
+fn render_sprite(
+    framebuffer: &mut Framebuffer,
+    camera: &Camera,
+    sprite: &Sprite,
+    texture_manager: &TextureManager,
+    size: f32,
+) {
+    let to_sprite = sprite.pos - camera.pos;
+
+    // Ángulo del sprite relativo a la dirección de la cámara, entre -PI y PI
+    let angle = (to_sprite.y.atan2(to_sprite.x) - camera.a + PI).rem_euclid(2.0 * PI) - PI;
+    let depth = to_sprite.length() * angle.cos();
+    if depth < 1.0 || angle.abs() >= PI / 2.0 {
+        return; // detrás de la cámara
+    }
+
+    let Some((texture_width, texture_height)) = texture_manager.size(sprite.texture) else {
+        return;
+    };
+
+    // Pie y cabeza del sprite proyectados con el ojo a media altura de la pared
+    let eye_height = size / 2.0;
+    let hh = camera.horizon();
+    let bottom = hh + (eye_height - sprite.offset * size) * camera.projection_distance / depth;
+    let top = hh + (eye_height - (sprite.offset + sprite.scale) * size) * camera.projection_distance / depth;
+    let height = bottom - top;
+    let width = height * texture_width as f32 / texture_height as f32;
+    if height < 1.0 {
+        return;
+    }
+
+    let center_x = framebuffer.width as f32 / 2.0 + angle.tan() * camera.projection_distance;
+    let left = center_x - width / 2.0;
+
+    let first_x = left.max(0.0) as u32;
+    let last_x = (left + width).min(framebuffer.width as f32).max(0.0) as u32;
+    let first_y = top.max(0.0) as u32;
+    let last_y = bottom.min(framebuffer.height as f32).max(0.0) as u32;
+
+    let shade_factor = distance_shade(depth);
+
+    for x in first_x..last_x {
+        // Una pared más cercana tapa esta columna del sprite
+        if framebuffer.depth_at(x) <= depth {
+            continue;
+        }
+
+        let u = (x as f32 + 0.5 - left) / width;
+        for y in first_y..last_y {
+            let v = (y as f32 + 0.5 - top) / height;
+            let color = texture_manager.sample(sprite.texture, u, v);
+            if color.a < 128 {
+                continue; // fondo transparente de la imagen
+            }
+
+            framebuffer.set_current_color(shade(Color::new(color.r, color.g, color.b, 255), shade_factor));
+            framebuffer.set_pixel(x, y);
+        }
+    }
+}
@@ -2,39 +2,50 @@
 
 use raylib::prelude::*;
 use std::collections::HashMap;
-use std::slice;
+
+use crate::enemy::EnemyKind;
+use crate::key::{KeyColor, key_image};
+
+// Copia de los píxeles de una imagen en memoria propia: a diferencia de
+// `Image`, se puede leer desde varios hilos a la vez
+struct Texels {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Texels {
+    fn from_image(image: &Image) -> Self {
+        Texels {
+            width: image.width as usize,
+            height: image.height as usize,
+            pixels: image.get_image_data().to_vec(),
+        }
+    }
+}
 
 pub struct TextureManager {
-    images: HashMap<char, Image>,       // Store images for pixel access
+    images: HashMap<char, Texels>,      // Store images for pixel access
     textures: HashMap<char, Texture2D>, // Store GPU textures for rendering
 }
 
 impl TextureManager {
-    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread) -> Self {
-        let mut images = HashMap::new();
+    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread, palette: &[(char, String)]) -> Self {
+        let images = load_images(palette);
         let mut textures = HashMap::new();
 
-        // Map characters to texture file paths
-        let texture_files = vec![
-            ('A', "assets/wall5.png"),
-            ('R', "assets/wall5.png"),
-            ('V', "assets/wall6.png"),
-            ('M', "assets/wall6.png"),
-            ('B', "assets/wall1.png"),
-            ('T', "assets/wall1.png"),
-            ('P', "assets/wall4.png"),
-            ('N', "assets/wall4.png"),
-            ('g', "assets/wall3.png"),
-        ];
-
-        for (ch, path) in texture_files {
-            let image = Image::load_image(path).expect(&format!("Failed to load image {}", path));
-            let texture = rl.load_texture(thread, path).expect(&format!("Failed to load texture {}", path));
-            images.insert(ch, image);
-            textures.insert(ch, texture);
+        for (ch, image) in &images {
+            if let Ok(texture) = rl.load_texture_from_image(thread, image) {
+                textures.insert(*ch, texture);
+            }
         }
 
-        TextureManager { images, textures }
+        TextureManager { images: to_texels(&images), textures }
+    }
+
+    // Solo las imágenes, sin texturas de GPU: sirve sin ventana (modo headless)
+    pub fn headless(palette: &[(char, String)]) -> Self {
+        TextureManager { images: to_texels(&load_images(palette)), textures: HashMap::new() }
     }
 
     pub fn get_pixel_color(&self, ch: char, tx: u32, ty: u32) -> Color {
@@ -44,9 +55,9 @@ impl TextureManager {
             let normalized_x = (tx as f32 / texture_size * image.width as f32) as u32;
             let normalized_y = (ty as f32 / texture_size * image.height as f32) as u32;
             
-            let x = normalized_x.min(image.width as u32 - 1) as i32;
-            let y = normalized_y.min(image.height as u32 - 1) as i32;
-            get_pixel_color(image, x, y)
+            let x = normalized_x.min(image.width as u32 - 1) as usize;
+            let y = normalized_y.min(image.height as u32 - 1) as usize;
+            image.pixels.get(y * image.width + x).copied().unwrap_or(Color::WHITE)
         } else {
             // Si no hay textura, usar color sólido como respaldo
             println!("⚠ No hay textura para '{}', usando color sólido", ch);
@@ -60,38 +71,133 @@ impl TextureManager {
                 'P' => Color::new(255, 105, 180, 255), // Hot Pink
                 'N' => Color::new(255, 140, 0, 255),   // Dark Orange
                 'g' => Color::GRAY,
+                'f' => Color::new(90, 60, 140, 255),
+                'c' => Color::new(35, 30, 70, 255),
                 _ => Color::WHITE,
             }
         }
     }
 
+    // Ancho y alto en píxeles de la imagen original
+    pub fn size(&self, ch: char) -> Option<(usize, usize)> {
+        self.images.get(&ch).map(|image| (image.width, image.height))
+    }
+
+    // Color en coordenadas normalizadas (u, v entre 0.0 y 1.0), con su transparencia
+    pub fn sample(&self, ch: char, u: f32, v: f32) -> Color {
+        match self.images.get(&ch) {
+            Some(image) => {
+                let x = ((u * image.width as f32) as usize).min(image.width - 1);
+                let y = ((v * image.height as f32) as usize).min(image.height - 1);
+                image.pixels[y * image.width + x]
+            }
+            None => Color::BLANK,
+        }
+    }
+
     pub fn get_texture(&self, ch: char) -> Option<&Texture2D> {
         self.textures.get(&ch)
     }
 }
 
-fn get_pixel_color(image: &Image, x: i32, y: i32) -> Color {
-    let width = image.width as usize;
-    let height = image.height as usize;
-
-    if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
-        return Color::WHITE;
+fn load_images(palette: &[(char, String)]) -> HashMap<char, Image> {
+    let mut images = HashMap::new();
+
+    // Map characters to texture file paths
+    let mut texture_files = vec![
+        ('A', "assets/wall5.png"),
+        ('R', "assets/wall5.png"),
+        ('V', "assets/wall6.png"),
+        ('M', "assets/wall6.png"),
+        ('B', "assets/wall1.png"),
+        ('T', "assets/wall1.png"),
+        ('P', "assets/wall4.png"),
+        ('N', "assets/wall4.png"),
+        ('g', "assets/wall3.png"),
+    ];
+
+    // La paleta del nivel reemplaza o agrega texturas
+    for (ch, path) in palette {
+        texture_files.retain(|(existing, _)| existing != ch);
+        texture_files.push((*ch, path.as_str()));
     }
 
-    let x = x as usize;
-    let y = y as usize;
+    for (ch, path) in texture_files {
+        let image = Image::load_image(path).expect(&format!("Failed to load image {}", path));
+        images.insert(ch, image);
+    }
 
-    let data_len = width * height * 4;
+    // Texturas de piso y techo; si falta el archivo se genera un patrón a cuadros
+    let surface_files = vec![
+        ('f', "assets/floor.png", Color::new(90, 60, 140, 255), Color::new(120, 85, 175, 255)),
+        ('c', "assets/ceiling.png", Color::new(35, 30, 70, 255), Color::new(50, 45, 95, 255)),
+        ('s', "assets/switch.png", Color::new(160, 30, 30, 255), Color::new(220, 60, 60, 255)),
+    ];
 
-    unsafe {
-        let data = slice::from_raw_parts(image.data as *const u8, data_len);
+    for (ch, path, color_a, color_b) in surface_files {
+        if images.contains_key(&ch) {
+            continue; // ya la definió la paleta del nivel
+        }
+        let image = Image::load_image(path)
+            .unwrap_or_else(|_| Image::gen_image_checked(128, 128, 32, 32, color_a, color_b));
+        images.insert(ch, image);
+    }
 
-        let idx = (y * width + x) * 4;
+    // Puertas: tablas horizontales si no hay imagen
+    if !images.contains_key(&'D') {
+        let image = Image::load_image("assets/door.png").unwrap_or_else(|_| {
+            Image::gen_image_checked(128, 128, 128, 16, Color::new(120, 75, 35, 255), Color::new(150, 100, 50, 255))
+        });
+        images.insert('D', image);
+    }
 
-        if idx + 3 >= data_len {
-            return Color::WHITE;
+    // Sprites: los personajes usan las ilustraciones del menú; los que no
+    // tienen archivo propio se generan como un resplandor con fondo transparente
+    let sprite_files = vec![
+        ('j', "assets/childhood.png", Color::new(255, 215, 0, 255)),  // Alegría
+        ('x', "assets/teen.png", Color::new(255, 140, 0, 255)),       // Ansiedad
+        ('e', "assets/adulthood.png", Color::new(138, 43, 226, 255)), // Envidia
+        ('b', "assets/bingbong.png", Color::new(255, 105, 180, 255)), // Bing Bong
+        ('o', "assets/orb.png", Color::new(255, 240, 120, 255)),      // orbe de memoria
+        (EnemyKind::Forgetter.texture(), "assets/forgetter.png", EnemyKind::Forgetter.color()),
+        (EnemyKind::MindWorker.texture(), "assets/mindworker.png", EnemyKind::MindWorker.color()),
+    ];
+
+    for (ch, path, glow) in sprite_files {
+        if images.contains_key(&ch) {
+            continue;
         }
+        let image = Image::load_image(path).unwrap_or_else(|_| {
+            Image::gen_image_gradient_radial(64, 64, 0.3, glow, Color::new(glow.r, glow.g, glow.b, 0))
+        });
+        images.insert(ch, image);
+    }
 
-        Color::new(data[idx], data[idx + 1], data[idx + 2], data[idx + 3])
+    // Llaves: se dibujan con el color de su emoción
+    for key in KeyColor::ALL {
+        images.entry(key.texture()).or_insert_with(|| key_image(key));
     }
-}
\ No newline at end of file
+
+    images
+}
+
+fn to_texels(images: &HashMap<char, Image>) -> HashMap<char, Texels> {
+    images.iter().map(|(ch, image)| (*ch, Texels::from_image(image))).collect()
+}
+
+// Oscurece un color según la distancia (factor de 0.0 a 1.0), sin tocar la transparencia
+pub fn shade(color: Color, factor: f32) -> Color {
+    Color::new(
+        (color.r as f32 * factor) as u8,
+        (color.g as f32 * factor) as u8,
+        (color.b as f32 * factor) as u8,
+        color.a,
+    )
+}
+
+// Factor de `shade` para algo a una distancia perpendicular `distance`: de
+// 1.0 pegado a la cámara a 0.3 desde 800 unidades. Paredes, piso y sprites
+// usan el mismo para que no se note la diferencia.
+pub fn distance_shade(distance: f32) -> f32 {
+    (1.0 - (distance / 800.0).min(1.0)) * 0.7 + 0.3
+}
@@ -0,0 +1,287 @@
+// validate.rs
+
+use std::collections::VecDeque;
+
+use crate::door::{DOOR, door_axis};
+use crate::key::{Inventory, KeyColor};
+use crate::maze::{Level, Maze, MazeError, cell_at, find_cells, is_walkable, neighbors, parse_level, world_to_cell};
+
+// Llaves con su celda, en el orden en que se juntan
+pub type KeyOrder = Vec<(KeyColor, (usize, usize))>;
+
+// Resultado de revisar un nivel: los errores lo hacen injugable, las
+// advertencias solo señalan cosas raras para los diseñadores
+pub struct Report {
+    pub errors: Vec<MazeError>,
+    pub warnings: Vec<MazeError>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+pub fn validate(level: &Level) -> Report {
+    let mut report = Report { errors: Vec::new(), warnings: Vec::new() };
+    let maze = &level.maze;
+    let expected = maze.first().map_or(0, |row| row.len());
+
+    for (j, row) in maze.iter().enumerate() {
+        if !level.open && row.len() != expected {
+            let (line, _) = level.file_position((0, j));
+            report.errors.push(MazeError::RaggedRow { line, expected, found: row.len() });
+        }
+        for (i, &cell) in row.iter().enumerate() {
+            if !level.is_known_cell(cell) {
+                let (line, column) = level.file_position((i, j));
+                report.errors.push(MazeError::UnknownCell { line, column, cell });
+            }
+        }
+    }
+
+    if level.goals.is_empty() {
+        report.errors.push(MazeError::MissingGoal);
+    }
+
+    let (line, column) = level.file_position(level.spawn);
+    if !is_walkable(cell_at(maze, level.spawn.0 as isize, level.spawn.1 as isize)) {
+        report.errors.push(MazeError::SpawnBlocked { line, column });
+        return report;
+    }
+
+    let (reachable, keys) = flood_with_keys(level);
+    let is_reachable = |(i, j): (usize, usize)| reachable[j][i];
+
+    // Llaves encerradas o detrás de un candado que nunca se abre
+    for &(key, cell) in level.keys.iter().filter(|key| !keys.contains(key)) {
+        let (line, column) = level.file_position(cell);
+        report.errors.push(MazeError::UnreachableKey { line, column, key });
+    }
+
+    // Celdas alcanzables que tocan el exterior: el jugador podría salirse del maze
+    if !level.open {
+        for (j, row) in maze.iter().enumerate() {
+            for (i, &cell) in row.iter().enumerate() {
+                let leaks = neighbors(i, j).iter().any(|&(ni, nj)| cell_at(maze, ni, nj).is_none());
+                if cell == ' ' && leaks && is_reachable((i, j)) {
+                    let (line, column) = level.file_position((i, j));
+                    report.errors.push(MazeError::MissingWall { line, column });
+                }
+            }
+        }
+    }
+
+    // Salidas: todas alcanzables y formando una sola zona
+    let reached_goals: Vec<(usize, usize)> = level.goals.iter().copied().filter(|&goal| is_reachable(goal)).collect();
+    if reached_goals.is_empty() {
+        report.errors.push(MazeError::NoPath { line, column });
+    }
+    for &goal in level.goals.iter().filter(|&&goal| !is_reachable(goal)) {
+        let (line, column) = level.file_position(goal);
+        report.errors.push(MazeError::UnreachableGoal { line, column });
+    }
+    let exits = regions(maze, &reached_goals, |cell| cell == Some('g')).len();
+    if exits > 1 {
+        report.errors.push(MazeError::MultipleGoals { count: exits });
+    }
+
+    // Zonas muertas: espacio caminable al que nunca se puede llegar
+    let unreachable: Vec<(usize, usize)> = maze
+        .iter()
+        .enumerate()
+        .flat_map(|(j, row)| row.iter().enumerate().map(move |(i, &cell)| (i, j, cell)))
+        .filter(|&(i, j, cell)| cell == ' ' && !is_reachable((i, j)))
+        .map(|(i, j, _)| (i, j))
+        .collect();
+    for zone in regions(maze, &unreachable, |cell| cell == Some(' ')) {
+        let (line, column) = level.file_position(zone[0]);
+        report.warnings.push(MazeError::DeadZone { line, column, cells: zone.len() });
+    }
+
+    // Las puertas se deslizan entre dos paredes enfrentadas
+    for (i, j) in find_cells(maze, DOOR) {
+        if door_axis(maze, i, j).is_none() {
+            let (line, column) = level.file_position((i, j));
+            report.errors.push(MazeError::DoorWithoutFrame { line, column });
+        }
+    }
+
+    // Sprites metidos en una pared: no se verían nunca
+    for sprite in &level.sprites {
+        let (i, j) = world_to_cell(sprite.pos, 1.0);
+        if !is_walkable(cell_at(maze, i, j)) {
+            let (line, column) = level.file_position((i.max(0) as usize, j.max(0) as usize));
+            report.warnings.push(MazeError::HiddenSprite { line, column, texture: sprite.texture });
+        }
+    }
+
+    report
+}
+
+// Celdas alcanzables desde la aparición juntando llaves: se inunda con las
+// celdas con candado bloqueadas, se juntan las llaves alcanzadas, se abren sus
+// colores y se repite hasta que no aparecen llaves nuevas. Devuelve lo
+// alcanzado y las llaves en el orden en que se consiguen
+pub fn flood_with_keys(level: &Level) -> (Vec<Vec<bool>>, KeyOrder) {
+    let mut inventory = Inventory::default();
+    let mut order = Vec::new();
+
+    loop {
+        let reached = flood_fill(&level.maze, level.spawn, |i, j| level.missing_key((i, j), &inventory).is_none());
+        let found: KeyOrder = level
+            .keys
+            .iter()
+            .copied()
+            .filter(|&(key, (i, j))| reached[j][i] && !order.contains(&(key, (i, j))))
+            .collect();
+        if found.is_empty() {
+            return (reached, order);
+        }
+        for &(key, _) in &found {
+            inventory.add(key);
+        }
+        order.extend(found);
+    }
+}
+
+// Celdas alcanzables caminando desde `start` (la salida termina el recorrido).
+// `passable` decide además qué celdas caminables se pueden pisar
+pub fn flood_fill<F>(maze: &Maze, start: (usize, usize), passable: F) -> Vec<Vec<bool>>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    let mut reached: Vec<Vec<bool>> = maze.iter().map(|row| vec![false; row.len()]).collect();
+    let mut queue = VecDeque::from([start]);
+    reached[start.1][start.0] = true;
+
+    while let Some((i, j)) = queue.pop_front() {
+        if maze[j][i] == 'g' {
+            continue;
+        }
+        for (ni, nj) in neighbors(i, j) {
+            if !is_walkable(cell_at(maze, ni, nj)) {
+                continue;
+            }
+            let (ni, nj) = (ni as usize, nj as usize);
+            if passable(ni, nj) && !reached[nj][ni] {
+                reached[nj][ni] = true;
+                queue.push_back((ni, nj));
+            }
+        }
+    }
+
+    reached
+}
+
+// Agrupa celdas en zonas conectadas cuyas celdas cumplen `joins`
+fn regions(maze: &Maze, cells: &[(usize, usize)], joins: impl Fn(Option<char>) -> bool) -> Vec<Vec<(usize, usize)>> {
+    let mut seen: Vec<Vec<bool>> = maze.iter().map(|row| vec![false; row.len()]).collect();
+    let mut zones = Vec::new();
+
+    for &(si, sj) in cells {
+        if seen[sj][si] {
+            continue;
+        }
+        seen[sj][si] = true;
+
+        let mut zone = vec![(si, sj)];
+        let mut queue = VecDeque::from([(si, sj)]);
+        while let Some((i, j)) = queue.pop_front() {
+            for (ni, nj) in neighbors(i, j) {
+                if joins(cell_at(maze, ni, nj)) && !seen[nj as usize][ni as usize] {
+                    seen[nj as usize][ni as usize] = true;
+                    zone.push((ni as usize, nj as usize));
+                    queue.push_back((ni as usize, nj as usize));
+                }
+            }
+        }
+        zones.push(zone);
+    }
+
+    zones
+}
+
+// Modo de línea de comandos `--validate <archivo>...`: devuelve el código de salida
+pub fn run(files: &[String]) -> i32 {
+    if files.is_empty() {
+        eprintln!("uso: --validate <archivo> [<archivo>...]");
+        return 2;
+    }
+
+    let mut failed = false;
+
+    for filename in files {
+        // Se lee sin validar para poder reportar todos los problemas juntos
+        let level = match std::fs::read_to_string(filename) {
+            Ok(text) => parse_level(&text, filename),
+            Err(source) => Err(MazeError::Io { path: filename.clone(), source }),
+        };
+
+        let report = match level {
+            Ok(level) => validate(&level),
+            Err(e) => Report { errors: vec![e], warnings: Vec::new() },
+        };
+
+        if report.is_ok() {
+            println!("✓ {}", filename);
+        } else {
+            println!("❌ {}", filename);
+            failed = true;
+        }
+        for error in &report.errors {
+            println!("  error: {}", error);
+        }
+        for warning in &report.warnings {
+            println!("  advertencia: {}", warning);
+        }
+    }
+
+    if failed { 1 } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LEVEL_FILES;
+    use crate::maze::load_maze;
+    use crate::solver::par_steps;
+
+    const CORRIDOR: &str = "---\nAAAAAAAAA\nA@ D D gA\nAAAAAAAAA\n";
+
+    // Los niveles del menú tienen que cargar sin errores
+    #[test]
+    fn menu_levels_load() {
+        for filename in LEVEL_FILES {
+            if let Err(e) = load_maze(filename) {
+                panic!("{}: {}", filename, e);
+            }
+        }
+    }
+
+    // Cada llave abre el candado que lleva a la siguiente: el orden lo decide
+    // el recorrido, no el archivo
+    #[test]
+    fn keys_unlock_in_reachable_order() {
+        let header = "key: ansiedad 4, 1\nkey: alegria 2, 1\nlock: alegria 3, 1\nlock: ansiedad 5, 1\n";
+        let level = parse_level(&format!("{}{}", header, CORRIDOR), "prueba").unwrap();
+
+        let errors: Vec<String> = validate(&level).errors.iter().map(|e| e.to_string()).collect();
+        assert!(errors.is_empty(), "{:?}", errors);
+        let (_, keys) = flood_with_keys(&level);
+        assert_eq!(keys, vec![(KeyColor::Joy, (2, 1)), (KeyColor::Anxiety, (4, 1))]);
+        assert_eq!(par_steps(&level), Some(6));
+    }
+
+    // Una llave detrás de su propio candado no se puede conseguir
+    #[test]
+    fn key_behind_its_own_lock_is_unreachable() {
+        let header = "key: alegria 4, 1\nlock: alegria 3, 1\n";
+        let level = parse_level(&format!("{}{}", header, CORRIDOR), "prueba").unwrap();
+
+        let errors = validate(&level).errors;
+        assert!(errors.iter().any(|e| matches!(e, MazeError::UnreachableKey { key: KeyColor::Joy, .. })));
+        assert!(errors.iter().any(|e| matches!(e, MazeError::NoPath { .. })));
+        assert_eq!(par_steps(&level), None);
+    }
+}